@@ -1,4 +1,4 @@
-use cargo_fixit::{ops::fixit::FixitArgs, CargoResult};
+use cargo_fixit::{ops::fixit::FixitArgs, CliResult};
 use clap::Parser;
 
 #[derive(Debug, Parser)]
@@ -11,7 +11,7 @@ pub(crate) enum Command {
 }
 
 impl Command {
-    pub(crate) fn exec(self) -> CargoResult<()> {
+    pub(crate) fn exec(self) -> CliResult {
         match self {
             Self::Fixit(fixit) => fixit.exec(),
         }
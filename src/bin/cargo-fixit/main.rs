@@ -10,9 +10,11 @@ fn main() {
     let args = cli::Command::parse();
 
     if let Err(err) = args.exec() {
-        shell::error(&err).unwrap();
+        if let Some(error) = &err.error {
+            shell::error(error).unwrap();
+        }
 
-        std::process::exit(101);
+        std::process::exit(err.exit_code);
     }
 }
 
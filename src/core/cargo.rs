@@ -0,0 +1,76 @@
+use std::{
+    env,
+    path::PathBuf,
+    process::{Command, Stdio},
+    sync::OnceLock,
+};
+
+use anyhow::Context as _;
+
+use crate::CargoResult;
+
+static CARGO: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// The cargo binary to run
+///
+/// [`set_cargo`] overrides it, e.g. for a wrapper like `cross`. `CARGO` is set when run as
+/// `cargo fixit`; otherwise, e.g. when `cargo-fixit` is run directly, fall back to `cargo` on
+/// `PATH` and then to rustup's proxy in `CARGO_HOME`.
+pub(crate) fn get_cargo() -> CargoResult<&'static PathBuf> {
+    CARGO
+        .get_or_init(|| {
+            if let Some(cargo) = env::var_os("CARGO") {
+                return Some(cargo.into());
+            }
+            let on_path = Command::new("cargo")
+                .arg("--version")
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .is_ok_and(|s| s.success());
+            if on_path {
+                return Some("cargo".into());
+            }
+            let cargo_home = env::var_os("CARGO_HOME")
+                .map(PathBuf::from)
+                .or_else(|| env::home_dir().map(|h| h.join(".cargo")))?;
+            Some(
+                cargo_home
+                    .join("bin")
+                    .join(format!("cargo{}", env::consts::EXE_SUFFIX)),
+            )
+            .filter(|c| c.exists())
+        })
+        .as_ref()
+        .context("could not find cargo; set `CARGO` to the path of the cargo binary")
+}
+
+/// Run `cargo` instead of the cargo binary [`get_cargo`] would find, e.g. a wrapper
+///
+/// Has to be called before cargo is first run.
+pub fn set_cargo(cargo: PathBuf) {
+    let _ = CARGO.set(Some(cargo));
+}
+
+/// Run cargo and rustc from a rustup toolchain, e.g. `nightly`, rather than the active one
+///
+/// This sets `RUSTUP_TOOLCHAIN` for every process run afterwards so it has to be called before
+/// anything is run.
+pub fn set_toolchain(toolchain: &str) -> CargoResult<()> {
+    let output = Command::new("rustup")
+        .args(["which", "--toolchain", toolchain, "cargo"])
+        .output()
+        .context("`--toolchain` requires rustup")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "toolchain `{toolchain}` is not installed; \
+            install it with `rustup toolchain install {toolchain}`"
+        );
+    }
+    let cargo = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+
+    // cargo runs `rustc` through rustup's proxy, which picks the toolchain from here
+    env::set_var("RUSTUP_TOOLCHAIN", toolchain);
+    let _ = CARGO.set(Some(cargo));
+    Ok(())
+}
@@ -0,0 +1,129 @@
+//! Reading cargo's configuration
+//!
+//! See <https://doc.rust-lang.org/cargo/reference/config.html#hierarchical-structure>
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use cargo_util::paths;
+
+/// The parts of cargo's configuration cargo-fixit needs, from its files and `--config` arguments
+#[derive(Debug)]
+pub struct Config {
+    /// Each source, with the directory its relative paths start from, from the lowest to the
+    /// highest precedence
+    sources: Vec<(toml::Table, PathBuf)>,
+}
+
+impl Config {
+    /// Read the configuration files that apply in `cwd`, then the `--config` arguments in `cli`
+    ///
+    /// Like cargo, an argument naming a file is read as a configuration file, anything else as a
+    /// `key = value` pair. Sources that can't be read or parsed are ignored, cargo reports them.
+    pub fn load(cwd: &Path, cli: &[String]) -> Self {
+        let mut sources = config_files(cwd)
+            .into_iter()
+            .filter_map(|file| Some((read_table(&file)?, config_root(&file))))
+            .collect::<Vec<_>>();
+        for arg in cli {
+            let file = cwd.join(arg);
+            let source = if !arg.is_empty() && file.is_file() {
+                read_table(&file).map(|table| (table, config_root(&file)))
+            } else {
+                toml::from_str(arg)
+                    .ok()
+                    .map(|table| (table, cwd.to_owned()))
+            };
+            sources.extend(source);
+        }
+        Self { sources }
+    }
+
+    /// The `build.rustflags`, if set anywhere
+    ///
+    /// Arrays from different sources are joined, a string replaces whatever came before it.
+    pub fn build_rustflags(&self) -> Option<Vec<String>> {
+        let mut flags: Option<Vec<String>> = None;
+        for (table, _) in &self.sources {
+            match table.get("build").and_then(|build| build.get("rustflags")) {
+                Some(toml::Value::Array(values)) => flags.get_or_insert_with(Vec::new).extend(
+                    values
+                        .iter()
+                        .filter_map(toml::Value::as_str)
+                        .map(ToOwned::to_owned),
+                ),
+                Some(toml::Value::String(value)) => {
+                    flags = Some(value.split_whitespace().map(ToOwned::to_owned).collect());
+                }
+                _ => {}
+            }
+        }
+        flags
+    }
+
+    /// A program set as `build.<key>`, e.g. `build.rustc`, from the source with the highest
+    /// precedence
+    ///
+    /// Paths with a separator are relative to where they are set, bare names are looked up on
+    /// `PATH`. An empty value is returned as is, as it unsets wrappers.
+    pub fn build_program(&self, key: &str) -> Option<PathBuf> {
+        let (program, root) = self.sources.iter().rev().find_map(|(table, root)| {
+            let program = table.get("build")?.get(key)?.as_str()?;
+            Some((Path::new(program), root))
+        })?;
+        if program.components().count() > 1 {
+            Some(root.join(program))
+        } else {
+            Some(program.to_owned())
+        }
+    }
+}
+
+fn read_table(file: &Path) -> Option<toml::Table> {
+    let contents = paths::read(file).ok()?;
+    toml::from_str(&contents).ok()
+}
+
+/// Relative paths in a configuration file start from the parent of its directory, e.g. the
+/// directory holding `.cargo`
+fn config_root(file: &Path) -> PathBuf {
+    file.parent()
+        .and_then(Path::parent)
+        .unwrap_or(file)
+        .to_owned()
+}
+
+/// The configuration files that apply in `cwd`, from the lowest to the highest precedence
+fn config_files(cwd: &Path) -> Vec<PathBuf> {
+    let mut dirs = cwd
+        .ancestors()
+        .map(|dir| dir.join(".cargo"))
+        .collect::<Vec<_>>();
+    if let Some(home) = cargo_home() {
+        if !dirs.contains(&home) {
+            dirs.push(home);
+        }
+    }
+
+    dirs.iter()
+        .rev()
+        .filter_map(|dir| {
+            // Cargo prefers the extensionless file when both exist
+            ["config", "config.toml"]
+                .into_iter()
+                .map(|name| dir.join(name))
+                .find(|path| path.is_file())
+        })
+        .collect()
+}
+
+fn cargo_home() -> Option<PathBuf> {
+    if let Some(home) = env::var_os("CARGO_HOME") {
+        return Some(PathBuf::from(home));
+    }
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".cargo"))
+}
@@ -1,2 +1,6 @@
+pub mod cargo;
+pub mod config;
+pub mod rustc;
 pub mod shell;
 pub mod sysroot;
+pub mod workspace;
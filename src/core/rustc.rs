@@ -0,0 +1,124 @@
+use std::{env, fmt, path::PathBuf, process::Command, sync::OnceLock};
+
+use crate::core::config::Config;
+
+static RUSTC: OnceLock<Rustc> = OnceLock::new();
+static VERSION: OnceLock<Option<RustcVersion>> = OnceLock::new();
+static LINT_HELP: OnceLock<String> = OnceLock::new();
+
+/// The `rustc` cargo runs, and the wrapper it runs it through
+#[derive(Debug)]
+struct Rustc {
+    path: PathBuf,
+    wrapper: Option<PathBuf>,
+}
+
+impl Rustc {
+    /// Resolve rustc the way cargo does: `RUSTC`, then `build.rustc`, then `rustc` on `PATH`,
+    /// run through `RUSTC_WRAPPER` or `build.rustc-wrapper` unless that is empty
+    fn new(config: &Config) -> Self {
+        let path = env::var_os("RUSTC")
+            .map(PathBuf::from)
+            .or_else(|| config.build_program("rustc"))
+            .unwrap_or_else(|| "rustc".into());
+        let wrapper = env::var_os("RUSTC_WRAPPER")
+            .map(PathBuf::from)
+            .or_else(|| config.build_program("rustc-wrapper"))
+            .filter(|wrapper| !wrapper.as_os_str().is_empty());
+        Self { path, wrapper }
+    }
+
+    fn command(&self) -> Command {
+        match &self.wrapper {
+            Some(wrapper) => {
+                let mut command = Command::new(wrapper);
+                command.arg(&self.path);
+                command
+            }
+            None => Command::new(&self.path),
+        }
+    }
+}
+
+/// Resolve rustc with cargo's configuration, including its `--config` overrides
+///
+/// Has to be called before rustc is first run, otherwise only the configuration files in the
+/// current directory are read.
+pub fn set_rustc_config(config: &Config) {
+    let _ = RUSTC.set(Rustc::new(config));
+}
+
+fn rustc() -> Command {
+    RUSTC
+        .get_or_init(|| Rustc::new(&Config::load(&env::current_dir().unwrap_or_default(), &[])))
+        .command()
+}
+
+/// Version of the active `rustc`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RustcVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl RustcVersion {
+    /// The oldest `rustc` whose suggestions have been tested with cargo-fixit
+    pub const MINIMUM: RustcVersion = RustcVersion {
+        major: 1,
+        minor: 89,
+        patch: 0,
+    };
+
+    /// Parse the output of `rustc --version`, e.g. `rustc 1.89.0 (29483883e 2025-08-04)`
+    fn parse(version: &str) -> Option<Self> {
+        let version = version.split_whitespace().nth(1)?;
+        let version = version.split('-').next()?;
+        let mut parts = version.split('.').map(|p| p.parse::<u64>().ok());
+
+        Some(Self {
+            major: parts.next()??,
+            minor: parts.next()??,
+            patch: parts.next()??,
+        })
+    }
+}
+
+impl fmt::Display for RustcVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// The lints in a lint group, e.g. `rust-2021-compatibility`, as listed by `rustc -W help`
+pub(crate) fn lint_group(group: &str) -> Vec<String> {
+    LINT_HELP
+        .get_or_init(|| {
+            rustc()
+                .args(["-W", "help"])
+                .output()
+                .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+                .unwrap_or_default()
+        })
+        .lines()
+        .find_map(|line| {
+            let (name, lints) = line.trim().split_once(' ')?;
+            (name == group).then(|| {
+                lints
+                    .split(',')
+                    .map(|lint| lint.trim().replace('-', "_"))
+                    .collect()
+            })
+        })
+        .unwrap_or_default()
+}
+
+pub(crate) fn get_rustc_version() -> &'static Option<RustcVersion> {
+    VERSION.get_or_init(|| {
+        rustc()
+            .arg("--version")
+            .output()
+            .ok()
+            .and_then(|x| RustcVersion::parse(&String::from_utf8_lossy(&x.stdout)))
+    })
+}
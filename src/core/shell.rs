@@ -1,56 +1,506 @@
 #![allow(dead_code)]
 
 use anyhow::Context;
-use clap::builder::styling::Style;
+use clap::builder::styling::{AnsiColor, Style};
 use clap_cargo::style::{ERROR, HEADER, NOTE, WARN};
-use std::io::Write;
+use std::{fmt::Display, io::Write, sync::OnceLock};
 
 use crate::CargoResult;
 
+static RENDERER: OnceLock<Box<dyn Renderer>> = OnceLock::new();
+static VERBOSITY: OnceLock<Verbosity> = OnceLock::new();
+
+/// How much of cargo-fixit's own output is printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verbosity {
+    Verbose,
+    Normal,
+    /// Only warnings, errors, and diagnostics are printed
+    Quiet,
+}
+
+/// Renders cargo-fixit's own output
+///
+/// One renderer is selected at startup with [`set_renderer`] and is then used by all of the
+/// reporting functions in this module.
+pub trait Renderer: Send + Sync {
+    /// Print a styled action message.
+    fn status(&self, action: &str, message: &dyn Display) -> CargoResult<()>;
+
+    /// Print a styled error message.
+    fn error(&self, message: &dyn Display) -> CargoResult<()>;
+
+    /// Print a styled warning message.
+    fn warn(&self, message: &dyn Display) -> CargoResult<()>;
+
+    /// Print a styled note message.
+    fn note(&self, message: &dyn Display) -> CargoResult<()>;
+
+    /// Print a styled fixed message, given the lints behind the fixes
+    fn fixed(&self, file_name: &dyn Display, fixes: u32, lints: &[&str]) -> CargoResult<()>;
+
+    /// Report that the suggestions for a file were skipped, after any warning about it
+    fn skipped(&self, file_name: &dyn Display, reason: &str) -> CargoResult<()>;
+
+    /// Print a diagnostic as rendered by rustc
+    fn diagnostic(&self, rendered: &[u8]) -> CargoResult<()>;
+
+    /// Print a diagnostic that was left for the user, given where it points
+    fn remaining(&self, rendered: &[u8], _annotation: &Annotation<'_>) -> CargoResult<()> {
+        self.diagnostic(rendered)
+    }
+
+    /// Report how often each lint was fixed and left for the user, given as a table and as a
+    /// map of lint names to counts
+    fn stats(&self, table: &str, lints: &serde_json::Value) -> CargoResult<()>;
+
+    /// Report the summary of the run, once it is done
+    fn summary(&self, summary: &serde_json::Value) -> CargoResult<()>;
+}
+
+/// A diagnostic left for the user, for renderers that annotate the code it points at
+#[derive(Debug, Clone, Copy)]
+pub struct Annotation<'a> {
+    /// e.g. `warning` or `error`
+    pub level: &'a str,
+    /// The lint or error code, e.g. `unused_mut` or `E0308`
+    pub lint: Option<&'a str>,
+    pub message: &'a str,
+    pub location: Option<Location<'a>>,
+}
+
+/// A range of a file, with lines and columns starting at 1
+#[derive(Debug, Clone, Copy)]
+pub struct Location<'a> {
+    pub file: &'a str,
+    pub line_start: usize,
+    pub column_start: usize,
+    pub line_end: usize,
+    pub column_end: usize,
+}
+
+/// Select the renderer for the rest of the run
+///
+/// Has no effect if a renderer was already selected or used.
+pub fn set_renderer(renderer: Box<dyn Renderer>) {
+    let _ = RENDERER.set(renderer);
+}
+
+fn renderer() -> &'static dyn Renderer {
+    RENDERER.get_or_init(|| Box::new(HumanRenderer)).as_ref()
+}
+
+/// Select the verbosity for the rest of the run
+///
+/// Has no effect if a verbosity was already selected or used.
+pub fn set_verbosity(verbosity: Verbosity) {
+    let _ = VERBOSITY.set(verbosity);
+}
+
+pub fn verbosity() -> Verbosity {
+    *VERBOSITY.get_or_init(|| Verbosity::Normal)
+}
+
 /// Print a styled action message.
-pub fn status(action: &str, message: impl std::fmt::Display) -> CargoResult<()> {
-    print(action, message, HEADER, true)
+pub fn status(action: &str, message: impl Display) -> CargoResult<()> {
+    renderer().status(action, &message)
 }
 
 /// Print a styled error message.
-pub fn error(message: impl std::fmt::Display) -> CargoResult<()> {
-    print("error", message, ERROR, false)
+pub fn error(message: impl Display) -> CargoResult<()> {
+    renderer().error(&message)
 }
 
 /// Print a styled warning message.
-pub fn warn(message: impl std::fmt::Display) -> CargoResult<()> {
-    print("warning", message, WARN, false)
+pub fn warn(message: impl Display) -> CargoResult<()> {
+    renderer().warn(&message)
 }
 
 /// Print a styled warning message.
-pub fn note(message: impl std::fmt::Display) -> CargoResult<()> {
-    print("note", message, NOTE, false)
+pub fn note(message: impl Display) -> CargoResult<()> {
+    renderer().note(&message)
 }
 
 /// Print a styled fixed message
-pub fn fixed(file_name: impl std::fmt::Display, fixes: u32) -> CargoResult<()> {
-    status(
-        "Fixed",
-        format!(
-            "{file_name} ({fixes} {})",
-            if fixes == 1 { "fix" } else { "fixes" }
-        ),
-    )
+pub fn fixed(file_name: impl Display, fixes: u32, lints: &[&str]) -> CargoResult<()> {
+    renderer().fixed(&file_name, fixes, lints)
+}
+
+/// Report that the suggestions for a file were skipped
+pub fn skipped(file_name: impl Display, reason: &str) -> CargoResult<()> {
+    renderer().skipped(&file_name, reason)
+}
+
+/// Report the summary of the run
+pub fn summary(summary: &serde_json::Value) -> CargoResult<()> {
+    renderer().summary(summary)
+}
+
+/// Report how often each lint was fixed and left for the user
+pub fn stats(table: &str, lints: &serde_json::Value) -> CargoResult<()> {
+    renderer().stats(table, lints)
 }
 
 pub fn print_ansi_stderr(message: &[u8]) -> CargoResult<()> {
-    let mut stderr = anstream::stderr().lock();
-    stderr.write_all(message)?;
+    renderer().diagnostic(message)
+}
+
+/// Print a diagnostic that was left for the user
+pub fn remaining(rendered: &[u8], annotation: &Annotation<'_>) -> CargoResult<()> {
+    renderer().remaining(rendered, annotation)
+}
+
+/// Print a unified diff to stdout, colored when stdout supports it
+pub fn print_diff(diff: &str) -> CargoResult<()> {
+    write_diff(&mut anstream::stdout().lock(), diff)
+}
+
+/// Print a unified diff to stderr, colored when stderr supports it
+pub fn print_diff_stderr(diff: &str) -> CargoResult<()> {
+    write_diff(&mut anstream::stderr().lock(), diff)
+}
+
+fn write_diff(out: &mut impl Write, diff: &str) -> CargoResult<()> {
+    for line in diff.lines() {
+        let style = if line.starts_with("---") || line.starts_with("+++") {
+            Style::new().bold()
+        } else if line.starts_with("@@") {
+            AnsiColor::Cyan.on_default()
+        } else if line.starts_with('-') {
+            AnsiColor::Red.on_default()
+        } else if line.starts_with('+') {
+            AnsiColor::Green.on_default()
+        } else {
+            Style::new()
+        };
+        writeln!(out, "{style}{line}{style:#}")?;
+    }
     Ok(())
 }
 
+/// Ask a question on stderr and read the answer from stdin, or `None` at the end of input
+pub fn prompt(question: &str) -> CargoResult<Option<String>> {
+    {
+        let mut stderr = anstream::stderr().lock();
+        write!(stderr, "{HEADER}{question}{HEADER:#} ")?;
+        stderr.flush()?;
+    }
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(answer))
+}
+
+/// Whether stderr will render colors
+///
+/// Besides `--color`, this honors the `NO_COLOR`, `CLICOLOR`, and `CLICOLOR_FORCE` conventions
+/// and falls back to detecting the terminal.
+pub fn stderr_supports_color() -> bool {
+    anstream::AutoStream::choice(&std::io::stderr()) != anstream::ColorChoice::Never
+}
+
+/// Output in the style of Cargo shell messages
+#[derive(Debug, Default)]
+pub struct HumanRenderer;
+
+impl Renderer for HumanRenderer {
+    fn status(&self, action: &str, message: &dyn Display) -> CargoResult<()> {
+        print(action, message, HEADER, true)
+    }
+
+    fn error(&self, message: &dyn Display) -> CargoResult<()> {
+        print("error", message, ERROR, false)
+    }
+
+    fn warn(&self, message: &dyn Display) -> CargoResult<()> {
+        print("warning", message, WARN, false)
+    }
+
+    fn note(&self, message: &dyn Display) -> CargoResult<()> {
+        print("note", message, NOTE, false)
+    }
+
+    fn fixed(&self, file_name: &dyn Display, fixes: u32, _lints: &[&str]) -> CargoResult<()> {
+        self.status(
+            "Fixed",
+            &format!(
+                "{file_name} ({fixes} {})",
+                if fixes == 1 { "fix" } else { "fixes" }
+            ),
+        )
+    }
+
+    fn skipped(&self, _file_name: &dyn Display, _reason: &str) -> CargoResult<()> {
+        // Already explained by a warning or note
+        Ok(())
+    }
+
+    fn diagnostic(&self, rendered: &[u8]) -> CargoResult<()> {
+        let mut stderr = anstream::stderr().lock();
+        stderr.write_all(rendered)?;
+        Ok(())
+    }
+
+    fn stats(&self, table: &str, _lints: &serde_json::Value) -> CargoResult<()> {
+        let mut stderr = anstream::stderr().lock();
+        stderr.write_all(table.as_bytes())?;
+        Ok(())
+    }
+
+    fn summary(&self, _summary: &serde_json::Value) -> CargoResult<()> {
+        Ok(())
+    }
+}
+
+/// Output as one JSON object per line on stdout, for editors and CI
+///
+/// Like cargo's messages, each object has a `reason` naming what it reports.
+#[derive(Debug, Default)]
+pub struct JsonRenderer;
+
+impl JsonRenderer {
+    fn emit(&self, event: serde_json::Value) -> CargoResult<()> {
+        let mut stdout = std::io::stdout().lock();
+        writeln!(stdout, "{event}")?;
+        Ok(())
+    }
+
+    fn message(&self, reason: &str, message: &dyn Display) -> CargoResult<()> {
+        self.emit(serde_json::json!({
+            "reason": reason,
+            "message": format!("{message:#}"),
+        }))
+    }
+}
+
+impl Renderer for JsonRenderer {
+    fn status(&self, action: &str, message: &dyn Display) -> CargoResult<()> {
+        self.emit(serde_json::json!({
+            "reason": "status",
+            "action": action.to_lowercase(),
+            "message": message.to_string(),
+        }))
+    }
+
+    fn error(&self, message: &dyn Display) -> CargoResult<()> {
+        self.message("error", message)
+    }
+
+    fn warn(&self, message: &dyn Display) -> CargoResult<()> {
+        self.message("warning", message)
+    }
+
+    fn note(&self, message: &dyn Display) -> CargoResult<()> {
+        self.message("note", message)
+    }
+
+    fn fixed(&self, file_name: &dyn Display, fixes: u32, lints: &[&str]) -> CargoResult<()> {
+        self.emit(serde_json::json!({
+            "reason": "fixed",
+            "file": file_name.to_string(),
+            "fixes": fixes,
+            "lints": lints,
+        }))
+    }
+
+    fn skipped(&self, file_name: &dyn Display, reason: &str) -> CargoResult<()> {
+        self.emit(serde_json::json!({
+            "reason": "skipped",
+            "file": file_name.to_string(),
+            "skip_reason": reason,
+        }))
+    }
+
+    fn diagnostic(&self, rendered: &[u8]) -> CargoResult<()> {
+        let rendered = String::from_utf8_lossy(rendered);
+        self.emit(serde_json::json!({
+            "reason": "diagnostic",
+            "rendered": anstream::adapter::strip_str(rendered.trim_end()).to_string(),
+        }))
+    }
+
+    fn stats(&self, _table: &str, lints: &serde_json::Value) -> CargoResult<()> {
+        self.emit(serde_json::json!({
+            "reason": "stats",
+            "lints": lints,
+        }))
+    }
+
+    fn summary(&self, summary: &serde_json::Value) -> CargoResult<()> {
+        let mut event = serde_json::json!({ "reason": "summary" });
+        if let (Some(event), Some(summary)) = (event.as_object_mut(), summary.as_object()) {
+            event.extend(summary.clone());
+        }
+        self.emit(event)
+    }
+}
+
+/// Drops the status, note, and fixed messages of another renderer, for `--quiet`
+pub struct QuietRenderer(pub Box<dyn Renderer>);
+
+impl Renderer for QuietRenderer {
+    fn status(&self, _action: &str, _message: &dyn Display) -> CargoResult<()> {
+        Ok(())
+    }
+
+    fn error(&self, message: &dyn Display) -> CargoResult<()> {
+        self.0.error(message)
+    }
+
+    fn warn(&self, message: &dyn Display) -> CargoResult<()> {
+        self.0.warn(message)
+    }
+
+    fn note(&self, _message: &dyn Display) -> CargoResult<()> {
+        Ok(())
+    }
+
+    fn fixed(&self, _file_name: &dyn Display, _fixes: u32, _lints: &[&str]) -> CargoResult<()> {
+        Ok(())
+    }
+
+    fn skipped(&self, file_name: &dyn Display, reason: &str) -> CargoResult<()> {
+        self.0.skipped(file_name, reason)
+    }
+
+    fn diagnostic(&self, rendered: &[u8]) -> CargoResult<()> {
+        self.0.diagnostic(rendered)
+    }
+
+    fn remaining(&self, rendered: &[u8], annotation: &Annotation<'_>) -> CargoResult<()> {
+        self.0.remaining(rendered, annotation)
+    }
+
+    fn stats(&self, table: &str, lints: &serde_json::Value) -> CargoResult<()> {
+        self.0.stats(table, lints)
+    }
+
+    fn summary(&self, summary: &serde_json::Value) -> CargoResult<()> {
+        self.0.summary(summary)
+    }
+}
+
+/// Output in the style of Cargo shell messages, plus GitHub Actions workflow commands on stdout
+/// that annotate the code left for the user and the fixed files
+///
+/// See <https://docs.github.com/en/actions/reference/workflow-commands-for-github-actions>.
+#[derive(Debug, Default)]
+pub struct GithubRenderer;
+
+impl GithubRenderer {
+    fn command(
+        &self,
+        command: &str,
+        properties: &[(&str, String)],
+        message: &str,
+    ) -> CargoResult<()> {
+        let properties = properties
+            .iter()
+            .map(|(key, value)| format!("{key}={}", escape_property(value)))
+            .collect::<Vec<_>>()
+            .join(",");
+        let mut stdout = std::io::stdout().lock();
+        if properties.is_empty() {
+            writeln!(stdout, "::{command}::{}", escape_data(message))?;
+        } else {
+            writeln!(stdout, "::{command} {properties}::{}", escape_data(message))?;
+        }
+        Ok(())
+    }
+}
+
+impl Renderer for GithubRenderer {
+    fn status(&self, action: &str, message: &dyn Display) -> CargoResult<()> {
+        HumanRenderer.status(action, message)
+    }
+
+    fn error(&self, message: &dyn Display) -> CargoResult<()> {
+        HumanRenderer.error(message)?;
+        self.command("error", &[], &format!("{message:#}"))
+    }
+
+    fn warn(&self, message: &dyn Display) -> CargoResult<()> {
+        HumanRenderer.warn(message)?;
+        self.command("warning", &[], &format!("{message:#}"))
+    }
+
+    fn note(&self, message: &dyn Display) -> CargoResult<()> {
+        HumanRenderer.note(message)
+    }
+
+    fn fixed(&self, file_name: &dyn Display, fixes: u32, lints: &[&str]) -> CargoResult<()> {
+        HumanRenderer.fixed(file_name, fixes, lints)?;
+        self.command(
+            "notice",
+            &[
+                ("file", file_name.to_string()),
+                ("title", "cargo-fixit".to_owned()),
+            ],
+            &format!(
+                "Fixed {fixes} {} ({})",
+                if fixes == 1 {
+                    "suggestion"
+                } else {
+                    "suggestions"
+                },
+                lints.join(", ")
+            ),
+        )
+    }
+
+    fn skipped(&self, file_name: &dyn Display, reason: &str) -> CargoResult<()> {
+        HumanRenderer.skipped(file_name, reason)
+    }
+
+    fn diagnostic(&self, rendered: &[u8]) -> CargoResult<()> {
+        HumanRenderer.diagnostic(rendered)
+    }
+
+    fn remaining(&self, rendered: &[u8], annotation: &Annotation<'_>) -> CargoResult<()> {
+        self.diagnostic(rendered)?;
+        let command = match annotation.level {
+            "error" | "error: internal compiler error" => "error",
+            "warning" => "warning",
+            _ => "notice",
+        };
+        let mut properties = Vec::new();
+        if let Some(location) = &annotation.location {
+            properties.extend([
+                ("file", location.file.to_owned()),
+                ("line", location.line_start.to_string()),
+                ("endLine", location.line_end.to_string()),
+                ("col", location.column_start.to_string()),
+                ("endColumn", location.column_end.to_string()),
+            ]);
+        }
+        if let Some(lint) = annotation.lint {
+            properties.push(("title", lint.to_owned()));
+        }
+        self.command(command, &properties, annotation.message)
+    }
+
+    fn stats(&self, table: &str, lints: &serde_json::Value) -> CargoResult<()> {
+        HumanRenderer.stats(table, lints)
+    }
+
+    fn summary(&self, _summary: &serde_json::Value) -> CargoResult<()> {
+        Ok(())
+    }
+}
+
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
 /// Print a message with a colored title in the style of Cargo shell messages.
-fn print(
-    status: &str,
-    message: impl std::fmt::Display,
-    style: Style,
-    justified: bool,
-) -> CargoResult<()> {
+fn print(status: &str, message: &dyn Display, style: Style, justified: bool) -> CargoResult<()> {
     let mut stderr = anstream::stderr().lock();
     if justified {
         write!(stderr, "{style}{status:>12}{style:#}")?;
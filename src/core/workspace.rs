@@ -0,0 +1,61 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    sync::OnceLock,
+};
+
+use serde::Deserialize;
+
+use crate::core::cargo::get_cargo;
+
+static WORKSPACE: OnceLock<Option<Workspace>> = OnceLock::new();
+
+/// Layout of the workspace being fixed, as reported by `cargo metadata`
+#[derive(Debug, Deserialize)]
+pub struct Workspace {
+    /// Directory rustc is run from, so what the paths in diagnostics are relative to
+    #[serde(rename = "workspace_root")]
+    pub root: PathBuf,
+    /// The workspace members
+    pub packages: Vec<Package>,
+    /// Where cargo puts build artifacts, unless overridden with `--target-dir`
+    pub target_directory: PathBuf,
+}
+
+/// A workspace member
+#[derive(Debug, Deserialize)]
+pub struct Package {
+    pub name: String,
+    pub edition: String,
+}
+
+impl Workspace {
+    /// Where a file named in a diagnostic is on disk
+    pub fn resolve(&self, file: &str) -> PathBuf {
+        self.root.join(file)
+    }
+
+    /// How to show a file named in a diagnostic, relative to the workspace root when it is
+    /// inside of it
+    pub fn display(&self, file: &str, absolute: bool) -> String {
+        let path = self.resolve(file);
+        if absolute {
+            return path.display().to_string();
+        }
+        path.strip_prefix(&self.root)
+            .unwrap_or(Path::new(file))
+            .display()
+            .to_string()
+    }
+}
+
+pub(crate) fn get_workspace() -> &'static Option<Workspace> {
+    WORKSPACE.get_or_init(|| {
+        Command::new(get_cargo().ok()?)
+            .args(["metadata", "--format-version=1", "--no-deps"])
+            .output()
+            .ok()
+            .filter(|x| x.status.success())
+            .and_then(|x| serde_json::from_slice(&x.stdout).ok())
+    })
+}
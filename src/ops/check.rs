@@ -1,34 +1,648 @@
+//! Run `cargo check` (or `clippy`, `build`, ...) and stream its JSON messages
+//!
+//! [`CheckRunner`] takes care of the plumbing needed to collect diagnostics from cargo, so it can
+//! be reused independently of the fixing logic in [`crate::ops::fixit`].
+
+use std::{
+    env,
+    ffi::OsStr,
+    io::{BufRead, BufReader, Cursor},
+    path::PathBuf,
+    process::{Command, Stdio},
+    thread,
+    time::Duration,
+};
+
+use anyhow::Context as _;
 use rustfix::diagnostics::Diagnostic;
 use serde::Deserialize;
 
+use crate::{core::cargo::get_cargo, CargoResult};
+
+/// Spawns cargo and parses its JSON output into [`CheckOutput`]s
+#[derive(Debug, Clone)]
+pub struct CheckRunner {
+    subcommand: String,
+    args: Vec<String>,
+    ansi: bool,
+    retries: u32,
+    envs: Vec<(String, String)>,
+    warn: Vec<String>,
+    force_warn: Vec<String>,
+    nice: Option<i32>,
+    verbose: u8,
+    trailing_args: Vec<String>,
+    /// Arguments after `--` that were dropped as they conflict with cargo-fixit's own
+    overridden_args: Vec<(String, &'static str)>,
+    config_rustflags: Option<Vec<String>>,
+    hermetic_env: bool,
+    rustflags: Rustflags,
+}
+
+/// Environment variables that change which diagnostics are reported, or how, removed by
+/// [`CheckRunner::hermetic_env`]
+const NON_HERMETIC_ENV: &[&str] = &[
+    "RUSTFLAGS",
+    "CARGO_ENCODED_RUSTFLAGS",
+    "CARGO_BUILD_RUSTFLAGS",
+    "RUSTDOCFLAGS",
+    "CARGO_ENCODED_RUSTDOCFLAGS",
+    "RUSTC_LOG",
+    "CARGO_INCREMENTAL",
+    "CARGO_BUILD_INCREMENTAL",
+    "CLIPPY_CONF_DIR",
+];
+
+/// Environment variables that conflict with how cargo-fixit runs cargo, and why, removed unless
+/// set with [`CheckRunner::envs`]
+const CONFLICTING_ENV: &[(&str, &str)] = &[(
+    "RUSTC_BOOTSTRAP",
+    "it lets rustc accept unstable flags that change its output",
+)];
+
+impl CheckRunner {
+    /// Run `cargo <subcommand>`, e.g. `check` or `clippy`
+    pub fn new(subcommand: impl Into<String>) -> Self {
+        Self {
+            subcommand: subcommand.into(),
+            args: Vec::new(),
+            ansi: true,
+            retries: 0,
+            envs: Vec::new(),
+            warn: Vec::new(),
+            force_warn: Vec::new(),
+            nice: None,
+            verbose: 0,
+            trailing_args: Vec::new(),
+            overridden_args: Vec::new(),
+            config_rustflags: None,
+            hermetic_env: false,
+            rustflags: Rustflags::new(None, false),
+        }
+    }
+
+    /// Extra arguments for cargo, e.g. from [`CheckFlags::to_flags`]
+    ///
+    /// [`CheckFlags::to_flags`]: crate::util::cli::CheckFlags::to_flags
+    pub fn args(mut self, args: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// The `build.rustflags` from config files and `--config`, see [`Config::build_rustflags`]
+    ///
+    /// [`Config::build_rustflags`]: crate::core::config::Config::build_rustflags
+    pub fn config_rustflags(mut self, flags: Option<Vec<String>>) -> Self {
+        self.config_rustflags = flags;
+        self.rustflags = Rustflags::new(self.config_rustflags.clone(), self.hermetic_env);
+        self
+    }
+
+    /// Run cargo without the environment variables that change its diagnostics, e.g.
+    /// `RUSTFLAGS` or `RUSTC_LOG`, so the results don't depend on the machine
+    ///
+    /// Variables set with [`CheckRunner::envs`] are still passed.
+    pub fn hermetic_env(mut self, hermetic: bool) -> Self {
+        self.hermetic_env = hermetic;
+        self.rustflags = Rustflags::new(self.config_rustflags.clone(), self.hermetic_env);
+        self
+    }
+
+    /// The environment variables that are set but kept from cargo by
+    /// [`CheckRunner::hermetic_env`]
+    pub fn removed_env(&self) -> Vec<&'static str> {
+        if !self.hermetic_env {
+            return Vec::new();
+        }
+        NON_HERMETIC_ENV
+            .iter()
+            .copied()
+            .filter(|key| env::var_os(key).is_some())
+            .collect()
+    }
+
+    /// The environment variables that are set but kept from cargo as they conflict with how
+    /// cargo-fixit runs it, and why
+    pub fn conflicting_env(&self) -> Vec<(&'static str, &'static str)> {
+        CONFLICTING_ENV
+            .iter()
+            .copied()
+            .filter(|(key, _)| env::var_os(key).is_some())
+            .filter(|(key, _)| self.envs.iter().all(|(k, _)| k != key))
+            .collect()
+    }
+
+    /// Whether rendered diagnostics should include ANSI escape codes
+    pub fn ansi(mut self, ansi: bool) -> Self {
+        self.ansi = ansi;
+        self
+    }
+
+    /// The `--message-format` cargo is run with
+    fn message_format(&self) -> &'static str {
+        if self.ansi {
+            "json-diagnostic-rendered-ansi"
+        } else {
+            "json"
+        }
+    }
+
+    /// Retry cargo up to `retries` times when it fails for transient reasons, e.g. file locking
+    /// on network drives
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Extra environment variables for cargo, e.g. ones needed by build scripts
+    pub fn envs(mut self, envs: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.envs.extend(envs);
+        self
+    }
+
+    /// Lints or lint groups to warn about unless allowed in the code, e.g. `rust-2018-idioms`
+    pub fn warn(mut self, lints: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.warn.extend(lints.into_iter().map(Into::into));
+        self
+    }
+
+    /// Lints or lint groups to warn about even when allowed in the code, e.g.
+    /// `rust-2021-compatibility`
+    pub fn force_warn(mut self, lints: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.force_warn.extend(lints.into_iter().map(Into::into));
+        self
+    }
+
+    /// Run cargo with its scheduling priority adjusted by `nice`, through `nice(1)`
+    ///
+    /// Only supported on Unix, see [`CheckRunner::supports_nice`].
+    pub fn nice(mut self, nice: Option<i32>) -> Self {
+        self.nice = nice;
+        self
+    }
+
+    /// Whether [`CheckRunner::nice`] is supported on this platform
+    pub fn supports_nice() -> bool {
+        cfg!(unix)
+    }
+
+    /// Arguments passed after `--`, e.g. lint levels for `clippy`
+    ///
+    /// Arguments that conflict with cargo-fixit's own are dropped, see
+    /// [`CheckRunner::overridden_args`].
+    pub fn trailing_args(mut self, args: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let (args, overridden) = strip_conflicting_flags(args.into_iter().map(Into::into));
+        self.trailing_args.extend(args);
+        self.overridden_args.extend(overridden);
+        self
+    }
+
+    /// Arguments passed after `--` that were dropped, and why
+    pub fn overridden_args(&self) -> &[(String, &'static str)] {
+        &self.overridden_args
+    }
+
+    /// Run cargo with `--verbose` `verbose` times, keeping its own output in [`CheckRun::stderr`]
+    pub fn verbose(mut self, verbose: u8) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Whether the cargo subcommand is installed
+    ///
+    /// Only meaningful for external subcommands that support `--version`, like `clippy`.
+    pub fn is_installed(&self) -> bool {
+        let Ok(cargo) = get_cargo() else {
+            return false;
+        };
+        Command::new(cargo)
+            .args([&self.subcommand, "--version"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|s| s.success())
+    }
+
+    /// The rustflags cargo will be run with
+    pub fn rustflags(&self) -> &Rustflags {
+        &self.rustflags
+    }
+
+    /// The command line cargo is run with, after the environment variables set for it, e.g.
+    /// `RUSTFLAGS=--cap-lints=warn cargo check --message-format json`
+    pub fn command_line(&self) -> CargoResult<String> {
+        let command = self.command()?;
+        let envs = command.get_envs().filter_map(|(key, value)| {
+            Some(format!("{}={}", key.to_string_lossy(), shell_quote(value?)))
+        });
+        let args = std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(shell_quote);
+        Ok(envs.chain(args).collect::<Vec<_>>().join(" "))
+    }
+
+    /// Run cargo to completion, retrying it after transient failures
+    ///
+    /// Nothing is printed, the retries and how cargo failed are part of the returned [`CheckRun`].
+    pub fn run(&self) -> CargoResult<CheckRun> {
+        let mut retries = Vec::new();
+        let output = loop {
+            let mut command = self.command()?;
+            let output = command.output().with_context(|| {
+                format!(
+                    "failed to run `{}`",
+                    command.get_program().to_string_lossy()
+                )
+            })?;
+            let attempt = retries.len() as u32;
+            if output.status.success() || attempt >= self.retries {
+                break output;
+            }
+            let Some(reason) = transient_failure(&String::from_utf8_lossy(&output.stderr)) else {
+                break output;
+            };
+
+            let delay = Duration::from_millis(500 * 2u64.pow(attempt));
+            retries.push(Retry { reason, delay });
+            thread::sleep(delay);
+        };
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let out_of_memory = !output.status.success() && out_of_memory(&stderr);
+        let future_incompat = future_incompat_packages(&stderr);
+
+        let buf = BufReader::new(Cursor::new(output.stdout));
+
+        Ok(CheckRun {
+            messages: buf
+                .lines()
+                .map_while(|l| l.ok())
+                .filter_map(|l| serde_json::from_str(&l).ok())
+                .collect(),
+            exit_code: output.status.code(),
+            future_incompat,
+            retries,
+            out_of_memory,
+            stderr: (self.verbose > 0).then_some(output.stderr),
+        })
+    }
+
+    fn command(&self) -> CargoResult<Command> {
+        let mut rustflags = self.rustflags.value.clone();
+        for lint in &self.warn {
+            rustflags.push_str(self.rustflags.separator);
+            rustflags.push_str(&format!("-W{lint}"));
+        }
+        for lint in &self.force_warn {
+            rustflags.push_str(self.rustflags.separator);
+            rustflags.push_str(&format!("--force-warn={lint}"));
+        }
+
+        let mut command = match self.nice {
+            Some(nice) if Self::supports_nice() => {
+                let mut command = Command::new("nice");
+                command.arg(format!("-n{nice}")).arg(get_cargo()?);
+                command
+            }
+            _ => Command::new(get_cargo()?),
+        };
+        if self.hermetic_env {
+            for key in NON_HERMETIC_ENV {
+                command.env_remove(key);
+            }
+        }
+        for (key, _) in CONFLICTING_ENV {
+            command.env_remove(key);
+        }
+        command
+            .arg(&self.subcommand)
+            .args(["--message-format", self.message_format()])
+            .args(&self.args)
+            .args((0..self.verbose).map(|_| "--verbose"))
+            .envs(self.envs.iter().map(|(k, v)| (k, v)))
+            // This allows `cargo fix` to work even if the crate has #[deny(warnings)].
+            .env(self.rustflags.key, rustflags)
+            .stderr(Stdio::piped())
+            .stdout(Stdio::piped());
+        if self.subcommand == "doc" {
+            // rustdoc isn't passed `RUSTFLAGS`, so its lints need capping separately
+            let (key, separator) =
+                if env::var_os("CARGO_ENCODED_RUSTDOCFLAGS").is_some() && !self.hermetic_env {
+                    ("CARGO_ENCODED_RUSTDOCFLAGS", "\x1f")
+                } else {
+                    ("RUSTDOCFLAGS", " ")
+                };
+            let mut rustdocflags = if self.hermetic_env {
+                String::new()
+            } else {
+                env::var(key).unwrap_or_default()
+            };
+            if !rustdocflags.is_empty() {
+                rustdocflags.push_str(separator);
+            }
+            rustdocflags.push_str("--cap-lints=warn");
+            command.env(key, rustdocflags);
+        }
+        if !self.trailing_args.is_empty() {
+            command.arg("--").args(&self.trailing_args);
+        }
+        Ok(command)
+    }
+}
+
+/// Quote `arg` for a POSIX shell, if needed
+fn shell_quote(arg: &OsStr) -> String {
+    let arg = arg.to_string_lossy();
+    let plain = |c: char| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c);
+    if !arg.is_empty() && arg.chars().all(plain) {
+        arg.into_owned()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// Check cargo's stderr for failures that may go away when retried, returning a description
+fn transient_failure(stderr: &str) -> Option<&'static str> {
+    const TRANSIENT: &[(&str, &str)] = &[
+        (
+            "Resource temporarily unavailable",
+            "resource temporarily unavailable",
+        ),
+        ("Device or resource busy", "resource busy"),
+        ("Text file busy", "file busy"),
+        ("being used by another process", "file in use"),
+        ("Stale file handle", "stale file handle"),
+        ("Resource deadlock avoided", "lock contention"),
+        ("locked a portion of the file", "lock contention"),
+        ("spurious network error", "network error"),
+        ("Connection reset by peer", "network error"),
+        ("Couldn't resolve host", "network error"),
+        ("Timeout was reached", "network timeout"),
+    ];
+
+    TRANSIENT
+        .iter()
+        .find(|(pattern, _)| stderr.contains(pattern))
+        .map(|(_, reason)| *reason)
+}
+
+/// Check cargo's stderr for a compiler that ran out of memory, either by failing to allocate or by
+/// being killed by the OOM killer
+///
+/// A compiler run through a wrapper, e.g. `sccache`, exits with 137 (128 + `SIGKILL`) instead.
+fn out_of_memory(stderr: &str) -> bool {
+    const OUT_OF_MEMORY: &[&str] = &[
+        "memory allocation of",
+        "out of memory",
+        "signal: 9, SIGKILL",
+        "exit status: 137)",
+    ];
+
+    OUT_OF_MEMORY.iter().any(|pattern| stderr.contains(pattern))
+}
+
+/// Check cargo's stderr for the packages with code that will be rejected by a future version of
+/// Rust, e.g. `bar v0.1.0`
+fn future_incompat_packages(stderr: &str) -> Vec<String> {
+    stderr
+        .lines()
+        .find_map(|l| l.split_once("will be rejected by a future version of Rust: "))
+        .map(|(_, packages)| packages.split(", ").map(|p| p.trim().to_owned()).collect())
+        .unwrap_or_default()
+}
+
+/// The rustflags passed to cargo, with `--cap-lints=warn` injected
+#[derive(Debug, Clone)]
+pub struct Rustflags {
+    /// The environment variable cargo reads the flags from
+    pub key: &'static str,
+    pub value: String,
+    separator: &'static str,
+    /// Where the user's flags came from, e.g. `RUSTFLAGS` or `build.rustflags`
+    pub source: &'static str,
+    /// User flags that were dropped as they conflict with the injected ones, and why
+    pub overridden: Vec<(String, &'static str)>,
+}
+
+impl Rustflags {
+    /// Combine the injected flags with the user's, ignoring the user's environment if `hermetic`
+    fn new(config_rustflags: Option<Vec<String>>, hermetic: bool) -> Self {
+        // `CARGO_ENCODED_RUSTFLAGS` takes precedence over `RUSTFLAGS`, so the flags need to be
+        // injected into whichever one cargo is going to read.
+        let (key, separator, source, user_flags): (_, _, _, Vec<String>) = if hermetic {
+            (
+                "RUSTFLAGS",
+                " ",
+                "build.rustflags",
+                config_rustflags.unwrap_or_default(),
+            )
+        } else if let Ok(flags) = env::var("CARGO_ENCODED_RUSTFLAGS") {
+            (
+                "CARGO_ENCODED_RUSTFLAGS",
+                "\x1f",
+                "CARGO_ENCODED_RUSTFLAGS",
+                flags
+                    .split('\x1f')
+                    .filter(|f| !f.is_empty())
+                    .map(ToOwned::to_owned)
+                    .collect(),
+            )
+        } else if let Ok(flags) = env::var("RUSTFLAGS") {
+            (
+                "RUSTFLAGS",
+                " ",
+                "RUSTFLAGS",
+                flags.split_whitespace().map(ToOwned::to_owned).collect(),
+            )
+        } else if let Ok(flags) = env::var("CARGO_BUILD_RUSTFLAGS") {
+            // Setting `RUSTFLAGS` hides `build.rustflags`, so carry over any set in config files,
+            // with `--config`, or through its environment variable
+            let env_flags = flags.split_whitespace().map(ToOwned::to_owned);
+            match config_rustflags {
+                Some(mut flags) => {
+                    flags.extend(env_flags);
+                    ("RUSTFLAGS", " ", "build.rustflags", flags)
+                }
+                None => (
+                    "RUSTFLAGS",
+                    " ",
+                    "CARGO_BUILD_RUSTFLAGS",
+                    env_flags.collect(),
+                ),
+            }
+        } else if let Some(flags) = config_rustflags {
+            ("RUSTFLAGS", " ", "build.rustflags", flags)
+        } else {
+            ("RUSTFLAGS", " ", "RUSTFLAGS", Vec::new())
+        };
+
+        let (user_flags, overridden) = strip_conflicting_flags(user_flags);
+        let mut flags = vec!["--cap-lints=warn".to_owned()];
+        flags.extend(user_flags);
+
+        Self {
+            key,
+            value: flags.join(separator),
+            separator,
+            source,
+            overridden,
+        }
+    }
+}
+
+/// Flags that conflict with the ones cargo-fixit passes to rustc or cargo, and why
+const CONFLICTING_FLAGS: &[(&str, &str)] = &[
+    ("--cap-lints", "cargo-fixit needs to control lint capping"),
+    (
+        "--error-format",
+        "cargo-fixit needs to read rustc's JSON diagnostics",
+    ),
+    (
+        "--json",
+        "cargo-fixit needs to read rustc's JSON diagnostics",
+    ),
+    (
+        "--message-format",
+        "cargo-fixit needs to read cargo's JSON messages",
+    ),
+];
+
+/// Split `flags` into the ones to keep and the ones that conflict with cargo-fixit's own, with
+/// why they conflict
+///
+/// Handles both `--flag value` and `--flag=value`, and `-Z unstable-options`, which enables
+/// unstable diagnostic formats.
+fn strip_conflicting_flags(
+    flags: impl IntoIterator<Item = String>,
+) -> (Vec<String>, Vec<(String, &'static str)>) {
+    let mut kept = Vec::new();
+    let mut overridden = Vec::new();
+    let mut flags = flags.into_iter().peekable();
+    while let Some(flag) = flags.next() {
+        if flag == "-Zunstable-options"
+            || (flag == "-Z" && flags.peek().map(String::as_str) == Some("unstable-options"))
+        {
+            let flag = if flag == "-Z" {
+                format!("-Z {}", flags.next().unwrap_or_default())
+            } else {
+                flag
+            };
+            overridden.push((flag, "it enables unstable diagnostic formats"));
+            continue;
+        }
+        let conflict = CONFLICTING_FLAGS.iter().find(|(name, _)| {
+            flag == *name
+                || flag
+                    .strip_prefix(name)
+                    .is_some_and(|rest| rest.starts_with('='))
+        });
+        match conflict {
+            Some((name, reason)) if flag == *name => {
+                let value = flags.next().unwrap_or_default();
+                overridden.push((format!("{flag} {value}").trim_end().to_owned(), *reason));
+            }
+            Some((_, reason)) => overridden.push((flag, *reason)),
+            None => kept.push(flag),
+        }
+    }
+    (kept, overridden)
+}
+
+/// The result of [`CheckRunner::run`]
+#[derive(Debug)]
+pub struct CheckRun {
+    pub messages: Vec<CheckOutput>,
+    pub exit_code: Option<i32>,
+    /// The packages cargo reported as having code that will be rejected by a future version of
+    /// Rust, e.g. `bar v0.1.0`
+    pub future_incompat: Vec<String>,
+    /// The transient failures cargo was retried after, in order
+    pub retries: Vec<Retry>,
+    /// Whether cargo failed as the compiler ran out of memory
+    pub out_of_memory: bool,
+    /// cargo's own output, kept with [`CheckRunner::verbose`]
+    pub stderr: Option<Vec<u8>>,
+}
+
+/// A transient failure cargo was retried after
+#[derive(Debug, Clone, Copy)]
+pub struct Retry {
+    /// e.g. `lock contention`
+    pub reason: &'static str,
+    /// How long cargo was waited for before the retry
+    pub delay: Duration,
+}
+
+/// A line of cargo's JSON output
 #[derive(Deserialize, Debug)]
 #[serde(untagged)]
 pub enum CheckOutput {
     Artifact(Artifact),
     Message(Message),
+    BuildScript(BuildScript),
 }
 
+/// A `compiler-artifact` message
 #[derive(Deserialize, Debug)]
 #[allow(dead_code)]
 pub struct Artifact {
     #[serde(flatten)]
     pub build_unit: BuildUnit,
+    /// Whether the artifact was up-to-date, i.e. cargo did not replay its diagnostics
     pub fresh: bool,
 }
 
+/// A `compiler-message` message
 #[derive(Deserialize, Debug)]
+#[serde(try_from = "RawMessage")]
 pub struct Message {
-    #[serde(flatten)]
     pub build_unit: BuildUnit,
+    /// The diagnostic's level, e.g. `error` or `warning`, which [`Diagnostic`] keeps private
+    pub level: String,
     pub message: Diagnostic,
 }
 
+#[derive(Deserialize)]
+struct RawMessage {
+    #[serde(flatten)]
+    build_unit: BuildUnit,
+    message: serde_json::Value,
+}
+
+impl TryFrom<RawMessage> for Message {
+    type Error = serde_json::Error;
+
+    fn try_from(raw: RawMessage) -> Result<Self, Self::Error> {
+        #[derive(Deserialize)]
+        struct Level {
+            level: String,
+        }
+
+        let Level { level } = Level::deserialize(&raw.message)?;
+        Ok(Self {
+            build_unit: raw.build_unit,
+            level,
+            message: Diagnostic::deserialize(raw.message)?,
+        })
+    }
+}
+
+/// A `build-script-executed` message
+#[derive(Deserialize, Debug)]
+pub struct BuildScript {
+    pub package_id: String,
+    /// Where the build script generates code into, i.e. its `OUT_DIR`
+    pub out_dir: PathBuf,
+}
+
+/// A target of a package, as compiled by a single rustc invocation
 #[derive(Deserialize, Hash, PartialEq, Clone, Eq, Debug)]
 pub struct BuildUnit {
     pub package_id: String,
     pub target: Target,
 }
 
+/// A cargo target, e.g. a library or a binary
 #[derive(Deserialize, Hash, PartialEq, Clone, Eq, Debug)]
 pub struct Target {
     kind: Vec<Kind>,
@@ -41,6 +655,21 @@ pub struct Target {
     test: bool,
 }
 
+impl Target {
+    pub fn kind(&self) -> &[Kind] {
+        &self.kind
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn edition(&self) -> &str {
+        &self.edition
+    }
+}
+
+/// The kind of a [`Target`]
 #[derive(Deserialize, Hash, PartialEq, Clone, Eq, Debug)]
 #[serde(rename_all(deserialize = "kebab-case"))]
 pub enum Kind {
@@ -59,6 +688,7 @@ pub enum Kind {
     Other(String),
 }
 
+/// The crate type a [`Target`] is compiled as
 #[derive(Deserialize, Hash, PartialEq, Clone, Eq, Debug)]
 #[serde(rename_all(deserialize = "kebab-case"))]
 pub enum CrateType {
@@ -72,3 +702,218 @@ pub enum CrateType {
     #[serde(untagged)]
     Other(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strip(flags: &[&str]) -> (Vec<String>, Vec<String>) {
+        let (kept, overridden) = strip_conflicting_flags(flags.iter().map(|&f| f.to_owned()));
+        (kept, overridden.into_iter().map(|(flag, _)| flag).collect())
+    }
+
+    #[test]
+    fn strip_conflicting_flags_keeps_others() {
+        assert_eq!(
+            strip(&["--cfg", "foo", "-Dwarnings", "-Zthreads=8"]),
+            (
+                vec![
+                    "--cfg".to_owned(),
+                    "foo".to_owned(),
+                    "-Dwarnings".to_owned(),
+                    "-Zthreads=8".to_owned(),
+                ],
+                vec![],
+            )
+        );
+    }
+
+    #[test]
+    fn strip_conflicting_flags_with_values() {
+        assert_eq!(
+            strip(&[
+                "--cap-lints",
+                "allow",
+                "--error-format=short",
+                "--json",
+                "artifacts",
+                "--message-format=human",
+                "--cfg",
+                "foo",
+            ]),
+            (
+                vec!["--cfg".to_owned(), "foo".to_owned()],
+                vec![
+                    "--cap-lints allow".to_owned(),
+                    "--error-format=short".to_owned(),
+                    "--json artifacts".to_owned(),
+                    "--message-format=human".to_owned(),
+                ],
+            )
+        );
+    }
+
+    #[test]
+    fn strip_conflicting_flags_unstable_options() {
+        assert_eq!(
+            strip(&[
+                "-Zunstable-options",
+                "-Z",
+                "unstable-options",
+                "-Z",
+                "threads=8"
+            ]),
+            (
+                vec!["-Z".to_owned(), "threads=8".to_owned()],
+                vec![
+                    "-Zunstable-options".to_owned(),
+                    "-Z unstable-options".to_owned(),
+                ],
+            )
+        );
+    }
+
+    #[test]
+    fn strip_conflicting_flags_missing_value() {
+        assert_eq!(
+            strip(&["--error-format"]),
+            (vec![], vec!["--error-format".to_owned()])
+        );
+    }
+
+    #[test]
+    fn rustflags_hermetic_uses_config() {
+        let rustflags = Rustflags::new(
+            Some(vec![
+                "--cfg".to_owned(),
+                "foo".to_owned(),
+                "--error-format=short".to_owned(),
+            ]),
+            true,
+        );
+        assert_eq!(rustflags.key, "RUSTFLAGS");
+        assert_eq!(rustflags.source, "build.rustflags");
+        assert_eq!(rustflags.value, "--cap-lints=warn --cfg foo");
+        assert_eq!(
+            rustflags.overridden,
+            vec![(
+                "--error-format=short".to_owned(),
+                "cargo-fixit needs to read rustc's JSON diagnostics"
+            )]
+        );
+    }
+
+    #[test]
+    fn transient_failure_file_busy() {
+        assert_eq!(
+            transient_failure(
+                "error: failed to remove file `target/debug/deps/libfoo.rlib`\n\n\
+                Caused by:\n  Device or resource busy (os error 16)\n"
+            ),
+            Some("resource busy")
+        );
+        assert_eq!(
+            transient_failure(
+                "error: failed to open: target/debug/.cargo-lock\n\n\
+                Caused by:\n  Stale file handle (os error 116)\n"
+            ),
+            Some("stale file handle")
+        );
+    }
+
+    #[test]
+    fn transient_failure_lock_contention() {
+        assert_eq!(
+            transient_failure(
+                "error: failed to lock file: target/debug/.cargo-lock\n\n\
+                Caused by:\n  Resource deadlock avoided (os error 35)\n"
+            ),
+            Some("lock contention")
+        );
+        assert_eq!(
+            transient_failure(
+                "error: failed to open: target\\debug\\.cargo-lock\n\n\
+                Caused by:\n  The process cannot access the file because another process has \
+                locked a portion of the file. (os error 33)\n"
+            ),
+            Some("lock contention")
+        );
+    }
+
+    #[test]
+    fn transient_failure_network() {
+        assert_eq!(
+            transient_failure(
+                "warning: spurious network error (3 tries remaining): [7] Couldn't connect to \
+                server\nerror: failed to download from `https://static.crates.io/crates/foo`\n"
+            ),
+            Some("network error")
+        );
+        assert_eq!(
+            transient_failure(
+                "error: failed to download `foo v0.1.0`\n\n\
+                Caused by:\n  [28] Timeout was reached (Operation timed out after 30000 \
+                milliseconds)\n"
+            ),
+            Some("network timeout")
+        );
+    }
+
+    #[test]
+    fn transient_failure_compile_error() {
+        assert_eq!(
+            transient_failure(
+                "error[E0308]: mismatched types\n --> src/lib.rs:1:22\n\n\
+                error: could not compile `foo` (lib) due to 1 previous error\n"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn out_of_memory_killed() {
+        assert!(out_of_memory(
+            "error: could not compile `foo` (lib)\n\n\
+            Caused by:\n  process didn't exit successfully: `rustc --crate-name foo` \
+            (signal: 9, SIGKILL: kill)\n"
+        ));
+        assert!(out_of_memory(
+            "error: could not compile `foo` (lib)\n\n\
+            Caused by:\n  process didn't exit successfully: `sccache rustc --crate-name foo` \
+            (exit status: 137)\n"
+        ));
+    }
+
+    #[test]
+    fn out_of_memory_allocation_failed() {
+        assert!(out_of_memory(
+            "memory allocation of 4294967296 bytes failed\n\
+            error: could not compile `foo` (lib)\n"
+        ));
+    }
+
+    #[test]
+    fn out_of_memory_compile_error() {
+        assert!(!out_of_memory(
+            "error[E0308]: mismatched types\n --> src/lib.rs:1:22\n\n\
+            error: could not compile `foo` (lib) due to 1 previous error\n"
+        ));
+        assert!(!out_of_memory(
+            "error: could not compile `foo` (lib)\n\n\
+            Caused by:\n  process didn't exit successfully: `rustc --crate-name foo` \
+            (exit status: 1)\n"
+        ));
+    }
+
+    #[test]
+    fn message_format() {
+        assert_eq!(
+            CheckRunner::new("check").message_format(),
+            "json-diagnostic-rendered-ansi"
+        );
+        assert_eq!(
+            CheckRunner::new("check").ansi(false).message_format(),
+            "json"
+        );
+    }
+}
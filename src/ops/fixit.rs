@@ -1,25 +1,44 @@
 use std::{
-    collections::HashSet,
-    env,
-    io::{BufRead, BufReader, Cursor},
-    path::Path,
-    process::Stdio,
+    collections::{BTreeSet, HashMap, HashSet},
+    env, mem,
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
 };
 
+use anyhow::Context as _;
 use cargo_util::paths;
-use clap::Parser;
+use clap::{ArgAction, Parser};
 use indexmap::{IndexMap, IndexSet};
 use rustfix::{collect_suggestions, CodeFix, Suggestion};
+use serde::Serialize;
 use tracing::{trace, warn};
 
 use crate::{
-    core::{shell, sysroot::get_sysroot},
-    ops::check::{BuildUnit, CheckOutput, Message},
+    core::{
+        cargo::{set_cargo, set_toolchain},
+        rustc::{get_rustc_version, lint_group, set_rustc_config, RustcVersion},
+        shell::{
+            self, GithubRenderer, HumanRenderer, JsonRenderer, QuietRenderer, Renderer, Verbosity,
+        },
+        sysroot::get_sysroot,
+        workspace::get_workspace,
+    },
+    ops::{
+        check::{BuildUnit, CheckOutput, CheckRun, CheckRunner, Kind, Message, Target},
+        review::Review,
+        scheduler::Scheduler,
+        summary::Summary,
+    },
     util::{
-        cli::CheckFlags, messages::gen_please_report_this_bug_text, package::format_package_id,
+        cli::CheckFlags,
+        diff::unified_diff,
+        lock::lock_workspace,
+        messages::gen_please_report_this_bug_text,
+        package::{format_package_id, package_name},
         vcs::VcsOpts,
     },
-    CargoResult,
+    CargoResult, CliError, CliResult,
 };
 
 #[derive(Debug, Parser)]
@@ -28,59 +47,654 @@ pub struct FixitArgs {
     #[arg(long)]
     clippy: bool,
 
+    /// Install missing toolchain components, like `clippy`, with rustup
+    #[arg(long)]
+    auto_install_components: bool,
+
+    /// Cargo command used to collect diagnostics
+    #[arg(long, value_enum, default_value_t = Mode::Check, conflicts_with = "clippy")]
+    mode: Mode,
+
+    /// Fix the lints for migrating packages to their next edition
+    #[arg(long)]
+    edition: bool,
+
+    /// Fix the idiom lints for the editions of the packages
+    #[arg(long)]
+    edition_idioms: bool,
+
+    /// Fix future-incompatibility lints and report the packages that still have them
+    #[arg(long)]
+    future_incompat_report: bool,
+
+    /// Only fix this lint, e.g. `unused_imports` (can be repeated)
+    #[arg(long, value_name = "LINT")]
+    only: Vec<String>,
+
+    /// Don't fix this lint, e.g. `clippy::needless_return` (can be repeated)
+    #[arg(long, value_name = "LINT")]
+    ignore: Vec<String>,
+
+    /// Only report diagnostics for targets of this kind, without fixing them
+    #[arg(long, value_name = "KIND", value_enum)]
+    report_only_kind: Vec<TargetKind>,
+
+    /// Write a summary of the run as JSON to PATH, or `-` for stdout
+    #[arg(long, value_name = "PATH")]
+    json_output: Option<PathBuf>,
+
+    /// Write every suggestion that was skipped, and why, as JSON to PATH
+    #[arg(long, value_name = "PATH")]
+    skip_ledger: Option<PathBuf>,
+
+    /// Print a table of how many fixes were applied and diagnostics remain for each lint
+    #[arg(long)]
+    stats: bool,
+
+    /// Write anonymous per-lint fix and skip counts as JSON to PATH
+    #[arg(long, value_name = "PATH")]
+    export_lint_stats: Option<PathBuf>,
+
+    /// Format of the diagnostics written to `--output`
+    #[arg(long, value_name = "FMT", value_enum, requires = "output")]
+    output_format: Option<OutputFormat>,
+
+    /// Write the diagnostics left over after fixing to FILE, or `-` for stdout
+    #[arg(long, value_name = "FILE", requires = "output_format")]
+    output: Option<PathBuf>,
+
+    /// Retry cargo up to N times when it fails for transient reasons
+    #[arg(long, value_name = "N", default_value_t = 2)]
+    cargo_retries: u32,
+
+    /// Check a target at most N times while its fixes keep changing the code
+    #[arg(
+        long,
+        value_name = "N",
+        env = "CARGO_FIX_MAX_RETRIES",
+        hide_env_values = true,
+        default_value_t = 4
+    )]
+    max_retries: usize,
+
+    /// Show paths as absolute rather than relative to the workspace root
+    #[arg(long)]
+    absolute_paths: bool,
+
+    /// Skip files larger than this, e.g. generated code included in the build
+    #[arg(long, value_name = "BYTES", default_value_t = 4 * 1024 * 1024)]
+    max_file_size: u64,
+
+    /// Don't warn when cargo modifies `Cargo.lock` during the run
+    #[arg(long)]
+    ignore_lockfile_changes: bool,
+
+    /// Set an environment variable for cargo, e.g. one needed by a build script
+    #[arg(long = "env", value_name = "KEY=VALUE", value_parser = parse_env)]
+    envs: Vec<(String, String)>,
+
+    /// Keep environment variables that change the diagnostics, like `RUSTFLAGS` and
+    /// `RUSTC_LOG`, from cargo
+    #[arg(long)]
+    hermetic_env: bool,
+
+    /// Number of files to fix in parallel, separate from cargo's jobs
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    apply_threads: usize,
+
+    /// Check once and apply the fixes from that check, without re-checking
+    #[arg(long)]
+    quick: bool,
+
+    /// Report the fixes a quick run would apply, without changing any files
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Like `--dry-run`, but exit with status 1 if anything could be fixed, e.g. for CI
+    #[arg(long, conflicts_with = "interactive")]
+    check: bool,
+
+    /// Print a unified diff of the fixes to stdout, e.g. for `git apply`
+    #[arg(long)]
+    diff: bool,
+
+    /// Ask before applying each suggestion
+    #[arg(long)]
+    interactive: bool,
+
+    /// Run cargo with its scheduling priority adjusted by N, like `nice -n N` (Unix only)
+    #[arg(long, value_name = "N", allow_negative_numbers = true)]
+    nice: Option<i32>,
+
+    /// Wait up to SECS for another run in the workspace to finish, rather than failing
+    #[arg(long, value_name = "SECS")]
+    wait_for_lock: Option<u64>,
+
+    /// Print each cargo command before running it, and include them in `--json-output`
+    #[arg(long)]
+    print_command: bool,
+
+    /// Run cargo and rustc from this rustup toolchain, e.g. `nightly`
+    #[arg(long, value_name = "NAME")]
+    toolchain: Option<String>,
+
+    /// Run this cargo binary or wrapper, e.g. `cross`
+    #[arg(
+        long,
+        value_name = "PATH",
+        env = "FIXIT_CARGO",
+        hide_env_values = true,
+        conflicts_with = "toolchain"
+    )]
+    cargo_path: Option<PathBuf>,
+
     /// Fix code even if it already has compiler errors
     #[arg(long)]
     broken_code: bool,
 
+    /// Also apply suggestions that may be incorrect, not only machine-applicable ones
+    #[arg(long, visible_alias = "yolo")]
+    apply_maybe_incorrect: bool,
+
+    /// Fix code even if the active toolchain is older than the tested minimum
+    #[arg(long)]
+    allow_old_toolchain: bool,
+
+    /// Use verbose output (-vv very verbose/build.rs output)
+    #[arg(short, long, action = ArgAction::Count, conflicts_with = "quiet")]
+    verbose: u8,
+
+    /// Do not print cargo log messages
+    #[arg(short, long)]
+    quiet: bool,
+
     #[command(flatten)]
     color: colorchoice_clap::Color,
 
+    /// How to print cargo-fixit's own output
+    #[arg(long, value_name = "FMT", value_enum, default_value_t = MessageFormat::Human)]
+    message_format: MessageFormat,
+
     #[command(flatten)]
     vcs_opts: VcsOpts,
 
     #[command(flatten)]
     check_flags: CheckFlags,
+
+    /// Arguments for clippy, e.g. `-W clippy::pedantic`
+    #[arg(last = true, value_name = "ARGS", requires = "clippy")]
+    clippy_args: Vec<String>,
 }
 
 impl FixitArgs {
-    pub fn exec(self) -> CargoResult<()> {
-        exec(self)
+    pub fn exec(self) -> CliResult {
+        let check = self.check;
+        let summary = exec(self)?;
+        if check && !summary.fixed.is_empty() {
+            let files = summary.fixed.len();
+            return Err(CliError::new(
+                anyhow::anyhow!(
+                    "{files} file{} can be fixed; run `cargo fixit` to apply the fixes",
+                    if files == 1 { "" } else { "s" },
+                ),
+                1,
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn parse_env(env: &str) -> Result<(String, String), String> {
+    let (key, value) = env
+        .split_once('=')
+        .ok_or_else(|| format!("expected `KEY=VALUE`, found `{env}`"))?;
+    if key.is_empty() {
+        return Err(format!("missing a key in `{env}`"));
+    }
+    Ok((key.to_owned(), value.to_owned()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Mode {
+    Check,
+    // `cargo check` under-reports for crates whose build scripts generate code used later on
+    Build,
+    // rustdoc's lints, e.g. bare URLs in doc comments
+    Doc,
+}
+
+impl Mode {
+    fn command(self) -> &'static str {
+        match self {
+            Mode::Check => "check",
+            Mode::Build => "build",
+            Mode::Doc => "doc",
+        }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    // SARIF 2.1.0, as uploaded to GitHub code scanning
+    Sarif,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum MessageFormat {
+    Human,
+    // One JSON object per line on stdout
+    Json,
+    // GitHub Actions workflow commands on stdout, annotating what was fixed and left
+    Github,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TargetKind {
+    Lib,
+    Bin,
+    Example,
+    Test,
+    Bench,
+    CustomBuild,
+}
+
+impl TargetKind {
+    pub(crate) fn matches(self, target: &Target) -> bool {
+        target.kind().iter().any(|kind| match self {
+            TargetKind::Lib => matches!(
+                kind,
+                Kind::Lib
+                    | Kind::Rlib
+                    | Kind::Dylib
+                    | Kind::Cdylib
+                    | Kind::Staticlib
+                    | Kind::ProcMacro
+            ),
+            TargetKind::Bin => kind == &Kind::Bin,
+            TargetKind::Example => kind == &Kind::Example,
+            TargetKind::Test => kind == &Kind::Test,
+            TargetKind::Bench => kind == &Kind::Bench,
+            TargetKind::CustomBuild => kind == &Kind::CustomBuild,
+        })
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            TargetKind::Lib => "lib",
+            TargetKind::Bin => "bin",
+            TargetKind::Example => "example",
+            TargetKind::Test => "test",
+            TargetKind::Bench => "bench",
+            TargetKind::CustomBuild => "build-script",
+        }
+    }
+}
+
+/// Why suggestions were not applied
+///
+/// The reasons up to [`SkipReason::OptedOut`] apply to all of the suggestions for a file, the
+/// rest to single suggestions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SkipReason {
+    /// The file no longer exists, e.g. it was moved or the diagnostics are stale
+    MissingFile,
+    /// The file exists but could not be read
+    Unreadable,
+    /// The file is larger than `--max-file-size`
+    TooLarge,
+    /// The file is not valid UTF-8 where the suggestions apply
+    NonUtf8,
+    /// The file starts with a `// fixit:skip-file` comment
+    OptedOut,
+    /// The suggestion could not be applied, e.g. as it overlaps with another one
+    FailedToApply,
+    /// The suggestion is for a target of a kind passed to `--report-only-kind`
+    ReportOnly,
+    /// The suggestion is for a lint passed to `--ignore`
+    Ignored,
+    /// The suggestion is for a lint that wasn't selected, with `--only` or by the lints being
+    /// migrated, e.g. with `--edition`
+    NotSelected,
+    /// The suggestion was declined with `--interactive`
+    Declined,
+}
+
 #[derive(Debug, Default)]
 struct File {
-    fixes: u32,
-    original_source: String,
+    /// Suggestions applied across all iterations, so ones that are re-reported aren't counted
+    /// twice, along with the lint that suggested them
+    applied: HashMap<Suggestion, Option<String>>,
+    original_source: Vec<u8>,
+    /// The source with the fixes applied so far, which is only on disk without `--dry-run`
+    source: Vec<u8>,
+}
+
+impl File {
+    fn lints(&self) -> impl ExactSizeIterator<Item = Option<&str>> {
+        self.applied.values().map(Option::as_deref)
+    }
+}
+
+/// Where a file named in a diagnostic is on disk
+fn resolve_path(file: &str) -> PathBuf {
+    get_workspace()
+        .as_ref()
+        .map(|w| w.resolve(file))
+        .unwrap_or_else(|| PathBuf::from(file))
+}
+
+/// The name of a file named in a diagnostic as it is on disk, relative to the workspace root when
+/// it is inside of it
+///
+/// On case-insensitive filesystems diagnostics can name the same file with different casing, so
+/// this is used to key files, and to compare them with protected directories.
+fn canonical_file_name(file: &str) -> (String, PathBuf) {
+    let Ok(path) = resolve_path(file).canonicalize() else {
+        return (file.to_owned(), PathBuf::from(file));
+    };
+    let name = get_workspace()
+        .as_ref()
+        .and_then(|w| w.root.canonicalize().ok())
+        .and_then(|root| path.strip_prefix(root).ok())
+        .unwrap_or(&path)
+        .to_str()
+        .unwrap_or(file)
+        .to_owned();
+    (name, path)
+}
+
+/// Whether `path` is inside `dir`, comparing canonical paths
+fn is_inside(path: &Path, dir: &Path) -> bool {
+    dir.canonicalize().is_ok_and(|dir| path.starts_with(dir)) || path.starts_with(dir)
+}
+
+/// How to show a file named in a diagnostic to the user
+fn display_path(file: &str, absolute: bool) -> String {
+    get_workspace()
+        .as_ref()
+        .map(|w| w.display(file, absolute))
+        .unwrap_or_else(|| file.to_owned())
+}
+
+fn lint_of(
+    (suggestion, remaining): &(Suggestion, Option<Remaining>),
+) -> (&Suggestion, Option<&str>) {
+    (
+        suggestion,
+        remaining.as_ref().and_then(|r| r.lint.as_deref()),
+    )
+}
+
+/// A diagnostic that was not fixed
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Remaining {
+    pub(crate) rendered: String,
+    /// The lint or error code, e.g. `unused_mut` or `E0308`
+    pub(crate) lint: Option<String>,
+    /// e.g. `warning` or `error`
+    pub(crate) level: String,
+    pub(crate) message: String,
+    /// Where the primary span of the diagnostic is, if it has one
+    pub(crate) span: Option<Span>,
+}
+
+/// A range of a file, with lines and columns starting at 1
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Span {
+    /// The canonical name of the file, see [`canonical_file_name`]
+    pub(crate) file: String,
+    pub(crate) line_start: usize,
+    pub(crate) column_start: usize,
+    pub(crate) line_end: usize,
+    pub(crate) column_end: usize,
+}
+
+/// Bail if more than one output is going to stdout, where they would be interleaved
+fn check_stdout_writers(args: &FixitArgs) -> CargoResult<()> {
+    let stdout = Some(Path::new("-"));
+    let writers = [
+        match args.message_format {
+            MessageFormat::Human => None,
+            MessageFormat::Json => Some("--message-format json"),
+            MessageFormat::Github => Some("--message-format github"),
+        },
+        (args.json_output.as_deref() == stdout).then_some("--json-output -"),
+        (args.output.as_deref() == stdout).then_some("--output -"),
+        args.diff.then_some("--diff"),
+    ];
+    if let [first, second, ..] = writers.into_iter().flatten().collect::<Vec<_>>()[..] {
+        anyhow::bail!("`{first}` and `{second}` both write to stdout; write one of them to a file");
+    }
+    Ok(())
 }
 
 #[tracing::instrument(skip_all)]
-fn exec(args: FixitArgs) -> CargoResult<()> {
+fn exec(mut args: FixitArgs) -> CargoResult<Summary> {
+    args.dry_run |= args.check;
+    // As with `cargo fix --edition`, so tests and examples are migrated too
+    if args.edition {
+        args.check_flags.default_to_all_targets();
+    }
     args.color.write_global();
+    let renderer: Box<dyn Renderer> = match args.message_format {
+        MessageFormat::Human => Box::new(HumanRenderer),
+        MessageFormat::Json => Box::new(JsonRenderer),
+        MessageFormat::Github => Box::new(GithubRenderer),
+    };
+    shell::set_renderer(if args.quiet {
+        Box::new(QuietRenderer(renderer))
+    } else {
+        renderer
+    });
+    shell::set_verbosity(match (args.verbose, args.quiet) {
+        (0, false) => Verbosity::Normal,
+        (0, true) => Verbosity::Quiet,
+        _ => Verbosity::Verbose,
+    });
+    check_stdout_writers(&args)?;
+    if let Some(toolchain) = &args.toolchain {
+        set_toolchain(toolchain)?;
+    }
+    if let Some(cargo) = &args.cargo_path {
+        set_cargo(cargo.clone());
+    }
+    let cargo_config = args.check_flags.cargo_config();
+    set_rustc_config(&cargo_config);
 
-    args.vcs_opts.valid_vcs()?;
+    // Nothing needs protecting from a dry run
+    if !args.dry_run {
+        args.vcs_opts.valid_vcs()?;
+    }
+    let _lock = lock_workspace(
+        args.check_flags.target_dir(),
+        args.wait_for_lock.map(Duration::from_secs),
+    )?;
+
+    if let Some(version) = get_rustc_version() {
+        if *version < RustcVersion::MINIMUM {
+            let message = format!(
+                "rustc {version} is older than {}, the oldest version cargo-fixit is tested with; \
+                its suggestions may corrupt your code",
+                RustcVersion::MINIMUM
+            );
+            if args.allow_old_toolchain {
+                shell::warn(message)?;
+            } else {
+                shell::note("try using `--allow-old-toolchain` to fix anyway")?;
+                anyhow::bail!(message);
+            }
+        }
+    }
 
-    let mut files: IndexMap<String, File> = IndexMap::new();
+    let mut only = HashSet::new();
+    let mut edition_groups = IndexSet::new();
+    let mut other_editions = HashMap::new();
+    if args.edition {
+        let groups = edition_lint_groups()?;
+        if groups.is_empty() {
+            shell::warn("all packages are on the latest edition, there is nothing to migrate")?;
+            return Ok(Summary::new());
+        }
+        let mut edition_lints = IndexMap::new();
+        for (edition, group) in groups {
+            let lints = group_lints(&group)?;
+            only.extend(lints.iter().cloned());
+            edition_lints.insert(edition, lints);
+            edition_groups.insert(group);
+        }
+        other_editions = other_edition_lints(&edition_lints);
+    }
 
-    let max_iterations: usize = env::var("CARGO_FIX_MAX_RETRIES")
-        .ok()
-        .and_then(|i| i.parse().ok())
-        .unwrap_or(4);
-    let mut iteration = 0;
+    let mut idiom_groups = IndexSet::new();
+    if args.edition_idioms {
+        idiom_groups = idiom_lint_groups()?;
+        // Don't let `--edition` filter out the idiom fixes
+        if !only.is_empty() {
+            for group in &idiom_groups {
+                only.extend(group_lints(group)?);
+            }
+        }
+    }
+
+    let mut future_incompat_groups = Vec::new();
+    if args.future_incompat_report {
+        future_incompat_groups.push("future-incompatible".to_owned());
+        if !only.is_empty() {
+            only.extend(group_lints("future-incompatible")?);
+        }
+    }
+
+    if !args.only.is_empty() {
+        let requested = args.only.iter().map(|lint| lint_name(lint));
+        if only.is_empty() {
+            only.extend(requested);
+        } else {
+            // Narrow down the lints fixed by the other options
+            only = requested.filter(|lint| only.contains(lint)).collect();
+            if only.is_empty() {
+                shell::warn("none of the lints passed to `--only` would be fixed")?;
+                return Ok(Summary::new());
+            }
+        }
+    }
+    let ignore: HashSet<_> = args.ignore.iter().map(|lint| lint_name(lint)).collect();
+
+    let runner = CheckRunner::new(if args.clippy {
+        "clippy"
+    } else {
+        args.mode.command()
+    })
+    .args(args.check_flags.to_flags())
+    // Dependencies can't be fixed, so documenting them is wasted work
+    .args((args.mode == Mode::Doc).then_some("--no-deps"))
+    .config_rustflags(cargo_config.build_rustflags())
+    .retries(args.cargo_retries)
+    .hermetic_env(args.hermetic_env)
+    .envs(args.envs.clone())
+    .warn(idiom_groups)
+    .force_warn(edition_groups)
+    .force_warn(future_incompat_groups)
+    .args(
+        args.future_incompat_report
+            .then_some("--future-incompat-report"),
+    )
+    .nice(args.nice)
+    .verbose(args.verbose)
+    .trailing_args(args.clippy_args.clone())
+    .args(args.quiet.then_some("--quiet"))
+    // Ask for plain diagnostics rather than stripping them when the terminal can't render ANSI.
+    .ansi(shell::stderr_supports_color());
+    if args.nice.is_some() && !CheckRunner::supports_nice() {
+        shell::warn("ignoring `--nice` as it is only supported on Unix")?;
+    }
+    if args.clippy && !runner.is_installed() {
+        if !args.auto_install_components {
+            shell::note(
+                "try installing it with `rustup component add clippy` \
+                or using `--auto-install-components`",
+            )?;
+            anyhow::bail!("`cargo clippy` is not installed for the active toolchain");
+        }
+        shell::status("Installing", "clippy")?;
+        let status = std::process::Command::new("rustup")
+            .args(["component", "add", "clippy"])
+            .status()?;
+        if !status.success() {
+            anyhow::bail!("failed to install clippy with rustup");
+        }
+    }
+
+    let rustflags = runner.rustflags();
+    for (flag, reason) in &rustflags.overridden {
+        shell::warn(format!(
+            "ignoring `{flag}` from `{}` as {reason}",
+            rustflags.source
+        ))?;
+    }
+    for (arg, reason) in runner.overridden_args() {
+        shell::warn(format!("ignoring `{arg}` after `--` as {reason}"))?;
+    }
+    let conflicting_env = runner.conflicting_env();
+    for (key, reason) in &conflicting_env {
+        shell::warn(format!(
+            "not passing `{key}` to cargo as {reason}; use `--env {key}=...` to pass it anyway"
+        ))?;
+    }
+    let removed_env = runner.removed_env();
+    if !removed_env.is_empty() {
+        shell::note(format!(
+            "not passing {} to cargo as `--hermetic-env` was passed",
+            removed_env
+                .iter()
+                .map(|key| format!("`{key}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))?;
+    }
+
+    let lockfile = match args.check_flags.lockfile_path() {
+        Some(path) => Some(path.to_owned()),
+        None => get_workspace().as_ref().map(|w| w.root.join("Cargo.lock")),
+    };
+    let original_lockfile = lockfile.as_ref().and_then(|l| std::fs::read(l).ok());
+
+    let mut files: IndexMap<String, File> = IndexMap::new();
+    let mut summary = Summary::new();
+    summary.removed_env = removed_env
+        .iter()
+        .chain(conflicting_env.iter().map(|(key, _)| key))
+        .map(|&key| key.to_owned())
+        .collect();
+    summary.dry_run = args.dry_run;
+    trace!("run_id={}", summary.run_id);
+
+    let max_iterations = args.max_retries;
+    let mut scheduler = Scheduler::new(max_iterations);
+    let mut review = Review::new();
 
     let mut last_errors = IndexMap::new();
-    let mut current_target: Option<BuildUnit> = None;
-    let mut seen = HashSet::new();
+    // Suggestions left for a later run by `--quick` as their file was already changed
+    let mut deferred = 0;
+    let mut fixed_packages = HashSet::new();
+    let mut future_incompat;
+    let maybe_incorrect = args.apply_maybe_incorrect || env::var("__CARGO_FIX_YOLO").is_ok();
 
     loop {
-        trace!("iteration={iteration}");
-        trace!("current_target={current_target:?}");
-        let (messages, exit_code) = check(&args)?;
+        trace!("iteration={}", scheduler.iteration());
+        trace!("current_target={:?}", scheduler.current());
+        let CheckRun {
+            messages,
+            exit_code,
+            future_incompat: packages,
+            ..
+        } = run_cargo(&runner, &args, &mut summary)?;
+        future_incompat = packages;
 
         if !args.broken_code && exit_code != Some(0) {
             let mut out = String::new();
 
-            if current_target.is_some() {
+            if scheduler.current().is_some() {
                 out.push_str(
                     "failed to automatically apply fixes suggested by rustc\n\n\
                     after fixes were automatically applied the \
@@ -90,20 +704,21 @@ fn exec(args: FixitArgs) -> CargoResult<()> {
                 for (
                     file,
                     File {
-                        fixes: _,
-                        original_source,
+                        original_source, ..
                     },
                 ) in &files
                 {
-                    out.push_str(&format!("  * {file}\n"));
-                    shell::note(format!("reverting `{file}` to its original state"))?;
-                    paths::write(file, original_source)?;
+                    let display = display_path(file, args.absolute_paths);
+                    out.push_str(&format!("  * {display}\n"));
+                    shell::note(format!("reverting `{display}` to its original state"))?;
+                    paths::write(resolve_path(file), original_source)?;
                 }
                 out.push('\n');
 
                 out.push_str(&gen_please_report_this_bug_text(args.clippy));
 
                 let mut errors = messages
+                    .into_iter()
                     .filter_map(|e| match e {
                         CheckOutput::Message(m) => m.message.rendered,
                         _ => None,
@@ -117,8 +732,9 @@ fn exec(args: FixitArgs) -> CargoResult<()> {
                     out.push_str(&format!("{}\n\n", e.trim_end()));
                 }
 
-                let (messages, _) = check(&args)?;
+                let CheckRun { messages, .. } = run_cargo(&runner, &args, &mut summary)?;
                 let mut errors = messages
+                    .into_iter()
                     .filter_map(|e| match e {
                         CheckOutput::Message(m) => m.message.rendered,
                         _ => None,
@@ -135,7 +751,7 @@ fn exec(args: FixitArgs) -> CargoResult<()> {
 
                 shell::warn(out)?;
             } else {
-                for e in messages.filter_map(|e| match e {
+                for e in messages.into_iter().filter_map(|e| match e {
                     CheckOutput::Message(m) => m.message.rendered,
                     _ => None,
                 }) {
@@ -147,21 +763,32 @@ fn exec(args: FixitArgs) -> CargoResult<()> {
             anyhow::bail!("could not compile");
         }
 
-        let (mut errors, build_unit_map) = collect_errors(messages, &seen);
+        let (mut errors, build_unit_map, skipped) = collect_errors(
+            messages.into_iter(),
+            scheduler.done(),
+            &args.report_only_kind,
+            &only,
+            &other_editions,
+            &ignore,
+            maybe_incorrect,
+        );
+        for ((file, reason), suggestions) in &skipped {
+            let display = display_path(file, args.absolute_paths);
+            summary.unapplied(&display, *reason, suggestions.iter().map(lint_of));
+        }
 
-        if iteration >= max_iterations {
-            if let Some(target) = current_target {
-                if seen.iter().all(|b| b.package_id != target.package_id) {
+        if scheduler.exhausted() {
+            if let Some(target) = scheduler.current().cloned() {
+                if scheduler.is_new_package(&target.package_id) {
                     shell::status("Checking", format_package_id(&target.package_id)?)?;
                 }
 
-                for (name, file) in files {
-                    shell::fixed(name, file.fixes)?;
-                }
+                report_fixed(&mut summary, &args, files)?;
                 files = IndexMap::new();
 
                 let mut errors = errors.shift_remove(&target).unwrap_or_else(IndexSet::new);
 
+                let capped = build_unit_map.get(&target).is_some_and(|f| !f.is_empty());
                 if let Some(e) = build_unit_map.get(&target) {
                     for (_, e) in e.iter().flat_map(|(_, s)| s) {
                         let Some(e) = e else {
@@ -171,21 +798,32 @@ fn exec(args: FixitArgs) -> CargoResult<()> {
                     }
                 }
                 for e in errors {
-                    shell::print_ansi_stderr(format!("{}\n\n", e.trim_end()).as_bytes())?;
+                    summary.diagnostic(&e)?;
+                }
+
+                if capped {
+                    shell::warn(format!(
+                        "stopped fixing {} after {max_iterations} check{} as its fixes kept \
+                        changing the code",
+                        format_package_id(&target.package_id)?,
+                        if max_iterations == 1 { "" } else { "s" },
+                    ))?;
+                    shell::note("raise the limit with `--max-retries`")?;
+                    summary.max_retries = Some(max_iterations);
                 }
 
-                seen.insert(target);
-                current_target = None;
-                iteration = 0;
+                scheduler.finish();
             } else {
                 break;
             }
         }
 
         let mut made_changes = false;
+        let had_target = scheduler.current().is_some();
+        scheduler.observe(build_unit_map.keys());
 
         for (build_unit, file_map) in build_unit_map {
-            if seen.contains(&build_unit) {
+            if scheduler.is_done(&build_unit) {
                 continue;
             }
 
@@ -193,92 +831,313 @@ fn exec(args: FixitArgs) -> CargoResult<()> {
                 .entry(build_unit.clone())
                 .or_insert_with(IndexSet::new);
 
-            if current_target.is_none() && file_map.is_empty() {
-                if seen.iter().all(|b| b.package_id != build_unit.package_id) {
+            // Without writing the fixes, re-checking would only find the same suggestions again
+            if args.quick || args.dry_run {
+                if scheduler.is_new_package(&build_unit.package_id) {
                     shell::status("Checking", format_package_id(&build_unit.package_id)?)?;
                 }
+
+                // Files shared between units, e.g. a library and its unit tests, were changed by
+                // an earlier unit, so only suggestions that weren't applied there are left over
+                let mut pending = IndexMap::new();
+                for (file, suggestions) in file_map {
+                    let Some(fixed) = files.get(&file) else {
+                        pending.insert(file, suggestions);
+                        continue;
+                    };
+                    for (suggestion, remaining) in suggestions {
+                        if !fixed.applied.contains_key(&suggestion) {
+                            deferred += 1;
+                            build_unit_errors.extend(remaining);
+                        }
+                    }
+                }
+                if fix_errors(
+                    &mut files,
+                    &mut summary,
+                    &args,
+                    &build_unit,
+                    pending,
+                    build_unit_errors,
+                    &mut review,
+                )? {
+                    fixed_packages.insert(build_unit.package_id.clone());
+                }
+
                 for e in build_unit_errors.iter() {
-                    shell::print_ansi_stderr(format!("{}\n\n", e.trim_end()).as_bytes())?;
+                    summary.diagnostic(e)?;
                 }
                 errors.shift_remove(&build_unit);
 
-                seen.insert(build_unit);
+                scheduler.skip(build_unit);
+            } else if scheduler.current().is_none() && file_map.is_empty() {
+                if scheduler.is_new_package(&build_unit.package_id) {
+                    shell::status("Checking", format_package_id(&build_unit.package_id)?)?;
+                }
+                for e in build_unit_errors.iter() {
+                    summary.diagnostic(e)?;
+                }
+                errors.shift_remove(&build_unit);
+
+                scheduler.skip(build_unit);
             } else if !file_map.is_empty()
-                && current_target.get_or_insert(build_unit.clone()) == &build_unit
-                && fix_errors(&mut files, file_map, build_unit_errors)?
+                && scheduler.select(&build_unit)
+                && fix_errors(
+                    &mut files,
+                    &mut summary,
+                    &args,
+                    &build_unit,
+                    file_map,
+                    build_unit_errors,
+                    &mut review,
+                )?
             {
+                fixed_packages.insert(build_unit.package_id.clone());
                 made_changes = true;
                 break;
             }
         }
 
         trace!("made_changes={made_changes:?}");
-        trace!("current_target={current_target:?}");
+        trace!("current_target={:?}", scheduler.current());
+
+        if !had_target {
+            if let Some(progress) = scheduler.progress() {
+                shell::status("Fixing", progress)?;
+            }
+        }
 
         last_errors = errors;
-        iteration += 1;
+        scheduler.next_iteration();
 
         if !made_changes {
-            if let Some(pkg) = current_target {
-                if seen.iter().all(|b| b.package_id != pkg.package_id) {
+            if let Some(pkg) = scheduler.current().cloned() {
+                if scheduler.is_new_package(&pkg.package_id) {
                     shell::status("Checking", format_package_id(&pkg.package_id)?)?;
                 }
 
-                for (name, file) in files {
-                    shell::fixed(name, file.fixes)?;
-                }
+                report_fixed(&mut summary, &args, files)?;
                 files = IndexMap::new();
 
                 let errors = last_errors.shift_remove(&pkg).unwrap_or_else(IndexSet::new);
                 for e in errors {
-                    shell::print_ansi_stderr(format!("{}\n\n", e.trim_end()).as_bytes())?;
+                    summary.diagnostic(&e)?;
                 }
 
-                seen.insert(pkg);
-                current_target = None;
-                iteration = 0;
+                scheduler.finish();
             } else {
                 break;
             }
         }
     }
 
-    for (name, file) in files {
-        shell::fixed(name, file.fixes)?;
-    }
+    report_fixed(&mut summary, &args, files)?;
 
     for e in last_errors.iter().flat_map(|(_, e)| e) {
-        shell::print_ansi_stderr(format!("{}\n\n", e.trim_end()).as_bytes())?;
+        summary.diagnostic(e)?;
+    }
+
+    let clean: BTreeSet<_> = scheduler
+        .units()
+        .iter()
+        .map(|b| &b.package_id)
+        .filter(|id| !fixed_packages.contains(*id))
+        .collect();
+    for id in clean {
+        let package = format_package_id(id)?;
+        if shell::verbosity() == Verbosity::Verbose {
+            shell::status("Clean", &package)?;
+        }
+        summary.clean.push(package);
     }
 
+    if args.future_incompat_report {
+        summary.future_incompat(future_incompat)?;
+    }
+
+    if args.apply_maybe_incorrect && !args.dry_run && !summary.fixed.is_empty() {
+        shell::warn("suggestions that may be incorrect were applied")?;
+        shell::note("review the changes before committing them")?;
+    }
+
+    if deferred > 0 && args.dry_run {
+        shell::note(format!(
+            "{deferred} suggestion{} overlapped with the fixes above and would be checked again \
+            after applying them",
+            if deferred == 1 { "" } else { "s" },
+        ))?;
+    } else if deferred > 0 {
+        shell::note(format!(
+            "{deferred} suggestion{} overlapped with fixes from this run; \
+            run `cargo fixit` without `--quick` to apply {}",
+            if deferred == 1 { "" } else { "s" },
+            if deferred == 1 { "it" } else { "them" },
+        ))?;
+    }
+
+    if let (Some(lockfile), Some(original), false) =
+        (&lockfile, &original_lockfile, args.ignore_lockfile_changes)
+    {
+        if std::fs::read(lockfile).ok().as_ref() != Some(original) {
+            shell::warn("`Cargo.lock` was modified by cargo while fixing")?;
+            shell::note(
+                "review the changes before committing, \
+                or use `--ignore-lockfile-changes` to silence this warning",
+            )?;
+        }
+    }
+
+    if args.stats {
+        summary.print_stats()?;
+    }
+
+    summary.report()?;
+
+    if let Some(path) = &args.json_output {
+        summary.write(path)?;
+    }
+
+    if let Some(path) = &args.skip_ledger {
+        summary.write_skip_ledger(path)?;
+    }
+
+    if let Some(path) = &args.export_lint_stats {
+        summary.write_lint_stats(path)?;
+    }
+
+    if let (Some(OutputFormat::Sarif), Some(path)) = (args.output_format, &args.output) {
+        summary.write_sarif(path)?;
+    }
+
+    Ok(summary)
+}
+
+/// Run cargo, printing the command first if `--print-command` was passed, and report its retries
+/// and failures
+fn run_cargo(
+    runner: &CheckRunner,
+    args: &FixitArgs,
+    summary: &mut Summary,
+) -> CargoResult<CheckRun> {
+    if args.print_command {
+        let command = runner.command_line()?;
+        shell::status("Running", format!("`{command}`"))?;
+        summary.commands.push(command);
+    }
+    let run = runner.run()?;
+
+    for (attempt, retry) in run.retries.iter().enumerate() {
+        shell::warn(format!(
+            "cargo failed ({}), retrying in {}ms ({}/{})",
+            retry.reason,
+            retry.delay.as_millis(),
+            attempt + 1,
+            args.cargo_retries
+        ))?;
+    }
+    if let Some(stderr) = &run.stderr {
+        shell::print_ansi_stderr(stderr)?;
+    }
+    if run.out_of_memory {
+        shell::warn("cargo failed as the compiler ran out of memory")?;
+        shell::note("try running fewer jobs in parallel with `--jobs`")?;
+    }
+    Ok(run)
+}
+
+/// Report the fixes applied to each file, along with their diffs for `--diff`
+fn report_fixed(
+    summary: &mut Summary,
+    args: &FixitArgs,
+    files: IndexMap<String, File>,
+) -> CargoResult<()> {
+    for (name, file) in files {
+        let display = display_path(&name, args.absolute_paths);
+        if args.diff {
+            shell::print_diff(&unified_diff(
+                &String::from_utf8_lossy(&file.original_source),
+                &String::from_utf8_lossy(&file.source),
+                &display,
+            ))?;
+        }
+        summary.fixed(display, file.lints())?;
+    }
     Ok(())
 }
 
-fn check(args: &FixitArgs) -> CargoResult<(impl Iterator<Item = CheckOutput>, Option<i32>)> {
-    let cmd = if args.clippy { "clippy" } else { "check" };
-    let command = std::process::Command::new(env!("CARGO"))
-        .args([cmd, "--message-format", "json-diagnostic-rendered-ansi"])
-        .args(args.check_flags.to_flags())
-        // This allows `cargo fix` to work even if the crate has #[deny(warnings)].
-        .env(
-            "RUSTFLAGS",
-            format!(
-                "--cap-lints=warn {}",
-                env::var("RUSTFLAGS").unwrap_or("".to_owned())
-            ),
-        )
-        .stderr(Stdio::piped())
-        .stdout(Stdio::piped())
-        .output()?;
+/// A lint as rustc names it in diagnostics, e.g. `unused_imports` for `unused-imports`
+fn lint_name(lint: &str) -> String {
+    lint.replace('-', "_")
+}
+
+/// The lint groups for migrating the workspace members to their next edition, by the edition
+/// they are on
+///
+/// Lints are set for the whole build, so members on different editions get the groups for all of
+/// them. Only the suggestions from the group for a target's own edition are applied, see
+/// [`other_edition_lints`].
+fn edition_lint_groups() -> CargoResult<IndexMap<String, String>> {
+    let workspace = get_workspace()
+        .as_ref()
+        .context("failed to determine the editions of the workspace members")?;
+    Ok(workspace
+        .packages
+        .iter()
+        .filter_map(|p| {
+            let next = match p.edition.as_str() {
+                "2015" => "2018",
+                "2018" => "2021",
+                "2021" => "2024",
+                _ => return None,
+            };
+            Some((p.edition.clone(), format!("rust-{next}-compatibility")))
+        })
+        .collect())
+}
+
+/// The lints in a lint group, failing if rustc doesn't list any
+///
+/// Without this, an unknown group would fix every lint instead of none.
+fn group_lints(group: &str) -> CargoResult<Vec<String>> {
+    let lints = lint_group(group);
+    if lints.is_empty() {
+        anyhow::bail!("rustc doesn't list any lints in `{group}`; is the toolchain too old?");
+    }
+    Ok(lints)
+}
 
-    let buf = BufReader::new(Cursor::new(command.stdout));
+/// The migration lints that don't apply to each edition, as they are for migrating from
+/// another one, given the lints for migrating from each edition
+fn other_edition_lints(
+    edition_lints: &IndexMap<String, Vec<String>>,
+) -> HashMap<String, HashSet<String>> {
+    edition_lints
+        .iter()
+        .map(|(edition, own)| {
+            let other = edition_lints
+                .values()
+                .flatten()
+                .filter(|lint| !own.contains(lint))
+                .cloned()
+                .collect();
+            (edition.clone(), other)
+        })
+        .collect()
+}
 
-    Ok((
-        buf.lines()
-            .map_while(|l| l.ok())
-            .filter_map(|l| serde_json::from_str(&l).ok()),
-        command.status.code(),
-    ))
+/// The idiom lint groups for the editions of the workspace members
+///
+/// Every edition since 2018 uses the 2018 idioms, and 2015 has none.
+fn idiom_lint_groups() -> CargoResult<IndexSet<String>> {
+    let workspace = get_workspace()
+        .as_ref()
+        .context("failed to determine the editions of the workspace members")?;
+    Ok(workspace
+        .packages
+        .iter()
+        .filter(|p| p.edition != "2015")
+        .map(|_| "rust-2018-idioms".to_owned())
+        .collect())
 }
 
 #[tracing::instrument(skip_all)]
@@ -286,18 +1145,34 @@ fn check(args: &FixitArgs) -> CargoResult<(impl Iterator<Item = CheckOutput>, Op
 fn collect_errors(
     messages: impl Iterator<Item = CheckOutput>,
     seen: &HashSet<BuildUnit>,
+    report_only: &[TargetKind],
+    only: &HashSet<String>,
+    other_editions: &HashMap<String, HashSet<String>>,
+    ignore: &HashSet<String>,
+    maybe_incorrect: bool,
 ) -> (
-    IndexMap<BuildUnit, IndexSet<String>>,
-    IndexMap<BuildUnit, IndexMap<String, IndexSet<(Suggestion, Option<String>)>>>,
+    IndexMap<BuildUnit, IndexSet<Remaining>>,
+    IndexMap<BuildUnit, IndexMap<String, IndexSet<(Suggestion, Option<Remaining>)>>>,
+    IndexMap<(String, SkipReason), IndexSet<(Suggestion, Option<Remaining>)>>,
 ) {
-    let only = HashSet::new();
     let mut build_unit_map = IndexMap::new();
 
     let mut errors = IndexMap::new();
 
+    // Suggestions that were filtered out by the user, by file and why
+    let mut skipped = IndexMap::new();
+
+    // Build units with suggestions for errors, and those suggestions
+    let mut error_units = HashSet::new();
+    let mut error_suggestions = HashSet::new();
+
+    // Where build scripts generate code into, which cargo reports before compiling the package
+    let mut out_dirs = Vec::new();
+
     for message in messages {
         let Message {
             build_unit,
+            level,
             message: diagnostic,
         } = match message {
             CheckOutput::Message(m) => m,
@@ -309,6 +1184,10 @@ fn collect_errors(
                 }
                 continue;
             }
+            CheckOutput::BuildScript(b) => {
+                out_dirs.push(b.out_dir);
+                continue;
+            }
         };
 
         let errors = errors
@@ -324,17 +1203,70 @@ fn collect_errors(
             .entry(build_unit.clone())
             .or_insert(IndexMap::new());
 
-        let filter = if env::var("__CARGO_FIX_YOLO").is_ok() {
+        let remaining = diagnostic.rendered.clone().map(|rendered| Remaining {
+            rendered,
+            lint: diagnostic.code.as_ref().map(|c| c.code.clone()),
+            level: level.clone(),
+            message: diagnostic.message.clone(),
+            span: diagnostic
+                .spans
+                .iter()
+                .find(|s| s.is_primary)
+                .map(|s| Span {
+                    file: canonical_file_name(&s.file_name).0,
+                    line_start: s.line_start,
+                    column_start: s.column_start,
+                    line_end: s.line_end,
+                    column_end: s.column_end,
+                }),
+        });
+
+        let filter = if maybe_incorrect {
             rustfix::Filter::Everything
         } else {
             rustfix::Filter::MachineApplicableOnly
         };
 
-        let Some(suggestion) = collect_suggestions(&diagnostic, &only, filter) else {
-            trace!("rejecting as not a MachineApplicable diagnosis: {diagnostic:?}");
-            if let Some(rendered) = diagnostic.rendered {
-                errors.insert(rendered);
+        let code = diagnostic.code.as_ref().map(|c| c.code.as_str());
+        let filtered = if report_only.iter().any(|k| k.matches(&build_unit.target)) {
+            trace!("rejecting as build unit `{:?}` is report-only", build_unit);
+            Some(SkipReason::ReportOnly)
+        } else if code.is_some_and(|c| ignore.contains(c)) {
+            trace!("rejecting as its lint is ignored: {diagnostic:?}");
+            Some(SkipReason::Ignored)
+        } else if !only.is_empty() && !code.is_some_and(|c| only.contains(c)) {
+            trace!("rejecting as its lint is not selected: {diagnostic:?}");
+            Some(SkipReason::NotSelected)
+        } else if code.is_some_and(|c| {
+            other_editions
+                .get(build_unit.target.edition())
+                .is_some_and(|lints| lints.contains(c))
+        }) {
+            trace!("rejecting as its lint migrates from another edition: {diagnostic:?}");
+            Some(SkipReason::NotSelected)
+        } else {
+            None
+        };
+        if let Some(reason) = filtered {
+            let suggestion = collect_suggestions(&diagnostic, &HashSet::new(), filter);
+            let file_name = suggestion
+                .as_ref()
+                .and_then(|s| s.solutions.first())
+                .and_then(|s| s.replacements.first())
+                .map(|r| canonical_file_name(&r.snippet.file_name).0);
+            if let (Some(suggestion), Some(file_name)) = (suggestion, file_name) {
+                skipped
+                    .entry((file_name, reason))
+                    .or_insert_with(IndexSet::new)
+                    .insert((suggestion, remaining.clone()));
             }
+            errors.extend(remaining);
+            continue;
+        }
+
+        let Some(suggestion) = collect_suggestions(&diagnostic, only, filter) else {
+            trace!("rejecting as not a MachineApplicable diagnosis: {diagnostic:?}");
+            errors.extend(remaining);
             continue;
         };
 
@@ -346,96 +1278,526 @@ fn collect_errors(
 
         let Some(file_name) = file_names.next() else {
             trace!("rejecting as it has no solutions {:?}", suggestion);
-            if let Some(rendered) = diagnostic.rendered {
-                errors.insert(rendered);
-            }
+            errors.extend(remaining);
             continue;
         };
 
-        if !file_names.all(|f| f == file_name) {
+        let (file_name, file_path) = canonical_file_name(file_name);
+        if !file_names.all(|f| canonical_file_name(f).0 == file_name) {
             trace!("rejecting as it changes multiple files: {:?}", suggestion);
-            if let Some(rendered) = diagnostic.rendered {
-                errors.insert(rendered);
-            }
+            errors.extend(remaining);
             continue;
         }
 
-        let file_path = Path::new(&file_name);
         // Do not write into registry cache. See rust-lang/cargo#9857.
         if let Ok(home) = env::var("CARGO_HOME") {
-            if file_path.starts_with(home) {
-                if let Some(rendered) = diagnostic.rendered {
-                    errors.insert(rendered);
-                }
+            if is_inside(&file_path, Path::new(&home)) {
+                errors.extend(remaining);
                 continue;
             }
         }
 
         if let Some(sysroot) = get_sysroot() {
-            if file_path.starts_with(sysroot) {
-                if let Some(rendered) = diagnostic.rendered {
-                    errors.insert(rendered);
-                }
+            if is_inside(&file_path, sysroot) {
+                errors.extend(remaining);
                 continue;
             }
         }
 
+        // Generated code is overwritten by its build script, so fixes never reach its template
+        if out_dirs.iter().any(|dir| is_inside(&file_path, dir)) {
+            trace!("rejecting as it changes generated code: {:?}", suggestion);
+            errors.extend(remaining);
+            continue;
+        }
+
+        if level == "error" {
+            error_units.insert(build_unit);
+            error_suggestions.insert(suggestion.clone());
+        }
+
         file_map
-            .entry(file_name.to_owned())
+            .entry(file_name)
             .or_insert_with(IndexSet::new)
-            .insert((suggestion, diagnostic.rendered));
+            .insert((suggestion, remaining));
+    }
+
+    // Fixing errors can change or hide other diagnostics, so they are fixed on their own first
+    // and the rest are picked up by the next check
+    for build_unit in &error_units {
+        let (Some(file_map), Some(errors)) = (
+            build_unit_map.get_mut(build_unit),
+            errors.get_mut(build_unit),
+        ) else {
+            continue;
+        };
+        for suggestions in file_map.values_mut() {
+            suggestions.retain(|(suggestion, remaining)| {
+                let keep = error_suggestions.contains(suggestion);
+                if !keep {
+                    trace!("deferring until errors are fixed: {:?}", suggestion);
+                    errors.extend(remaining.clone());
+                }
+                keep
+            });
+        }
+        file_map.retain(|_, suggestions| !suggestions.is_empty());
     }
 
-    (errors, build_unit_map)
+    (errors, build_unit_map, skipped)
 }
 
 #[tracing::instrument(skip_all)]
 fn fix_errors(
     files: &mut IndexMap<String, File>,
-    file_map: IndexMap<String, IndexSet<(Suggestion, Option<String>)>>,
-    errors: &mut IndexSet<String>,
+    summary: &mut Summary,
+    args: &FixitArgs,
+    build_unit: &BuildUnit,
+    file_map: IndexMap<String, IndexSet<(Suggestion, Option<Remaining>)>>,
+    errors: &mut IndexSet<Remaining>,
+    review: &mut Review,
 ) -> CargoResult<bool> {
-    let mut made_changes = false;
+    let mut pending = Vec::new();
     for (file, suggestions) in file_map {
-        let source = match paths::read(file.as_ref()) {
-            Ok(s) => s,
-            Err(e) => {
-                warn!("failed to read `{}`: {}", file, e);
+        if let Ok(metadata) = resolve_path(&file).metadata() {
+            if metadata.len() > args.max_file_size {
+                let display = display_path(&file, args.absolute_paths);
+                shell::warn(format!(
+                    "skipping suggestions for `{display}` as it is larger than {} bytes",
+                    args.max_file_size
+                ))?;
+                summary.skipped(
+                    display,
+                    SkipReason::TooLarge,
+                    suggestions.iter().map(lint_of),
+                )?;
                 errors.extend(suggestions.iter().filter_map(|(_, e)| e.clone()));
                 continue;
             }
-        };
+        }
+        pending.push((file, suggestions));
+    }
 
-        let mut fixed = CodeFix::new(&source);
-        let mut num_fixes = 0;
-
-        for (suggestion, rendered) in suggestions.iter().rev() {
-            match fixed.apply(suggestion) {
-                Ok(()) => num_fixes += 1,
-                Err(rustfix::Error::AlreadyReplaced {
-                    is_identical: true, ..
-                }) => {}
-                Err(e) => {
-                    if let Some(rendered) = rendered {
-                        errors.insert(rendered.to_owned());
+    if args.interactive {
+        for (file, suggestions) in &mut pending {
+            // Unreadable files are reported by `fix_file`. Suggestions in files that aren't valid
+            // UTF-8 may still apply before the first invalid byte, so those are shown lossily
+            let Ok(source) = paths::read_bytes(&resolve_path(file)) else {
+                continue;
+            };
+            let source = String::from_utf8_lossy(&source);
+            let display = display_path(file, args.absolute_paths);
+            for (suggestion, remaining) in mem::take(suggestions) {
+                let lint = remaining.as_ref().and_then(|r| r.lint.as_deref());
+                let rendered = remaining.as_ref().map(|r| r.rendered.as_str());
+                if review.accept(&suggestion, lint, rendered, &display, &source)? {
+                    suggestions.insert((suggestion, remaining));
+                } else {
+                    summary.unapplied(
+                        &display,
+                        SkipReason::Declined,
+                        std::iter::once((&suggestion, lint)),
+                    );
+                    errors.extend(remaining);
+                }
+            }
+        }
+        pending.retain(|(_, suggestions)| !suggestions.is_empty());
+    }
+
+    let results = parallel_map(&pending, args.apply_threads, |(file, suggestions)| {
+        fix_file(file, suggestions)
+    });
+
+    let mut made_changes = false;
+    for ((file, suggestions), result) in pending.into_iter().zip(results) {
+        let fixed = match result? {
+            Ok(fixed) => fixed,
+            Err(reason) => {
+                let display = display_path(&file, args.absolute_paths);
+                match reason {
+                    SkipReason::MissingFile => {
+                        shell::warn(format!(
+                            "skipping suggestions for `{display}` as it no longer exists"
+                        ))?;
+                        shell::note(format!(
+                            "the build output may be stale; try `cargo clean -p {}`",
+                            package_name(&build_unit.package_id)?
+                        ))?;
+                    }
+                    SkipReason::NonUtf8 => {
+                        shell::warn(format!(
+                            "skipping suggestions for `{display}` as it is not valid UTF-8"
+                        ))?;
                     }
-                    warn!("{e:?}");
+                    SkipReason::OptedOut => {
+                        shell::note(format!(
+                            "skipping suggestions for `{display}` as it has `{SKIP_FILE_MARKER}`"
+                        ))?;
+                    }
+                    SkipReason::Unreadable
+                    | SkipReason::TooLarge
+                    | SkipReason::FailedToApply
+                    | SkipReason::ReportOnly
+                    | SkipReason::Ignored
+                    | SkipReason::NotSelected
+                    | SkipReason::Declined => {}
                 }
+                summary.skipped(display, reason, suggestions.iter().map(lint_of))?;
+                errors.extend(suggestions.iter().filter_map(|(_, e)| e.clone()));
+                continue;
             }
+        };
+
+        let display = display_path(&file, args.absolute_paths);
+        for failures in &fixed.failures {
+            let message = failures.message(&display);
+            warn!("{message}");
         }
-        if fixed.modified() {
-            let new_source = fixed.finish()?;
-            paths::write(&file, new_source)?;
+        summary.unapplied(
+            &display,
+            SkipReason::FailedToApply,
+            fixed.failed.iter().map(lint_of),
+        );
+        errors.extend(fixed.failed.into_iter().filter_map(|(_, e)| e));
+        if let Some(new_source) = fixed.new_source {
+            if !args.dry_run {
+                paths::write(resolve_path(&file), &new_source)?;
+            }
             made_changes = true;
-            files
-                .entry(file)
-                .or_insert(File {
-                    fixes: 0,
-                    original_source: source,
-                })
-                .fixes += num_fixes;
+            let file = files.entry(file).or_insert(File {
+                applied: HashMap::new(),
+                original_source: fixed.original,
+                source: Vec::new(),
+            });
+            file.applied.extend(fixed.applied);
+            file.source = new_source;
         }
     }
 
     Ok(made_changes)
 }
+
+/// The outcome of applying the suggestions for a file
+struct Fixed {
+    original: Vec<u8>,
+    /// `None` if no suggestion could be applied
+    new_source: Option<Vec<u8>>,
+    /// The suggestions that were applied, along with the lint that suggested them
+    applied: Vec<(Suggestion, Option<String>)>,
+    /// Suggestions that failed to apply, along with their diagnostics
+    failed: Vec<(Suggestion, Option<Remaining>)>,
+    /// The failures grouped by the kind of error, as overlapping suggestions, e.g. in generated
+    /// code, can fail by the hundreds
+    failures: Vec<ApplyFailures>,
+}
+
+/// Apply the suggestions for a file, unless it couldn't be read
+///
+/// This may run on several threads at once, so all reporting and writing is left to the caller.
+fn fix_file(
+    file: &str,
+    suggestions: &IndexSet<(Suggestion, Option<Remaining>)>,
+) -> CargoResult<Result<Fixed, SkipReason>> {
+    let path = resolve_path(file);
+    let original = match paths::read_bytes(&path) {
+        Ok(s) => s,
+        Err(e) => {
+            warn!("failed to read `{}`: {}", file, e);
+            return Ok(Err(if path.exists() {
+                SkipReason::Unreadable
+            } else {
+                SkipReason::MissingFile
+            }));
+        }
+    };
+    if opts_out(&String::from_utf8_lossy(&original)) {
+        return Ok(Err(SkipReason::OptedOut));
+    }
+
+    // Suggestions that end before the first invalid byte can still be applied, leaving the rest
+    // of the file untouched
+    let valid_up_to = match std::str::from_utf8(&original) {
+        Ok(_) => original.len(),
+        Err(e) => e.valid_up_to(),
+    };
+    let in_valid_prefix = suggestions
+        .iter()
+        .flat_map(|(s, _)| &s.solutions)
+        .flat_map(|s| &s.replacements)
+        .all(|r| r.snippet.range.end <= valid_up_to);
+    if !in_valid_prefix {
+        warn!("`{file}` is not valid UTF-8 after byte {valid_up_to}");
+        return Ok(Err(SkipReason::NonUtf8));
+    }
+    let (source, rest) = original.split_at(valid_up_to);
+    let source = String::from_utf8_lossy(source).into_owned();
+
+    let mut fixed = apply_suggestions(source, suggestions)?;
+    if let Some(new_source) = &mut fixed.new_source {
+        new_source.extend_from_slice(rest);
+    }
+    fixed.original = original;
+    Ok(Ok(fixed))
+}
+
+/// Comment that excludes a file from all fixes when it appears before any code
+const SKIP_FILE_MARKER: &str = "// fixit:skip-file";
+
+/// Whether `source` has [`SKIP_FILE_MARKER`] among its leading comments and attributes
+fn opts_out(source: &str) -> bool {
+    source
+        .lines()
+        .map(str::trim)
+        .take_while(|l| l.is_empty() || l.starts_with("//") || l.starts_with("#!"))
+        .any(|l| l == SKIP_FILE_MARKER)
+}
+
+/// Apply the suggestions for a file to its contents
+fn apply_suggestions(
+    source: String,
+    suggestions: &IndexSet<(Suggestion, Option<Remaining>)>,
+) -> CargoResult<Fixed> {
+    let mut fixed = CodeFix::new(&source);
+    let mut applied = Vec::new();
+    let mut failed = Vec::new();
+    let mut failures = IndexMap::new();
+
+    for (suggestion, rendered) in suggestions.iter().rev() {
+        match fixed.apply(suggestion) {
+            Ok(()) => applied.push((
+                suggestion.clone(),
+                rendered.as_ref().and_then(|r| r.lint.clone()),
+            )),
+            Err(rustfix::Error::AlreadyReplaced {
+                is_identical: true, ..
+            }) => {}
+            Err(e) => {
+                failed.push((suggestion.clone(), rendered.clone()));
+                let lines = suggestion
+                    .snippets
+                    .first()
+                    .map(|s| (s.line_range.start.line, s.line_range.end.line));
+                let failures =
+                    failures
+                        .entry(mem::discriminant(&e))
+                        .or_insert_with(|| ApplyFailures {
+                            error: e,
+                            count: 0,
+                            lines: Vec::new(),
+                        });
+                failures.count += 1;
+                failures.lines.extend(lines);
+            }
+        }
+    }
+
+    let new_source = if fixed.modified() {
+        Some(fixed.finish()?.into_bytes())
+    } else {
+        None
+    };
+    Ok(Fixed {
+        original: source.into_bytes(),
+        new_source,
+        applied,
+        failed,
+        failures: failures.into_values().collect(),
+    })
+}
+
+/// How many of the lines with failed suggestions are listed, see [`ApplyFailures::message`]
+const MAX_LISTED_FAILURES: usize = 5;
+
+/// Suggestions for a file that failed to apply with the same kind of error
+struct ApplyFailures {
+    /// The error of the first suggestion, as an example
+    error: rustfix::Error,
+    count: usize,
+    /// The first and last line of each suggestion
+    lines: Vec<(usize, usize)>,
+}
+
+impl ApplyFailures {
+    /// Describe the failures, listing the lines of up to [`MAX_LISTED_FAILURES`] of them
+    fn message(&self, file: &str) -> String {
+        let count = self.count;
+        let mut sorted = self.lines.clone();
+        sorted.sort_unstable();
+        let mut lines = sorted
+            .iter()
+            .take(MAX_LISTED_FAILURES)
+            .map(|&(start, end)| {
+                if start == end {
+                    start.to_string()
+                } else {
+                    format!("{start}-{end}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        if self.lines.len() > MAX_LISTED_FAILURES {
+            lines.push_str(&format!(
+                " and {} more",
+                self.lines.len() - MAX_LISTED_FAILURES
+            ));
+        }
+        let at = match sorted.as_slice() {
+            [(start, end)] if start == end => format!("line {lines}"),
+            _ => format!("lines {lines}"),
+        };
+        let overlap = matches!(self.error, rustfix::Error::AlreadyReplaced { .. });
+        match (count, overlap) {
+            (1, true) => {
+                format!("failed to apply a suggestion to `{file}` at {at} as it overlaps with another one")
+            }
+            (_, true) => format!(
+                "failed to apply {count} suggestions to `{file}` at {at} as they overlap with \
+                other ones"
+            ),
+            (1, false) => format!(
+                "failed to apply a suggestion to `{file}` at {at}: {}",
+                self.error
+            ),
+            (_, false) => format!(
+                "failed to apply {count} suggestions to `{file}` at {at}, e.g. {}",
+                self.error
+            ),
+        }
+    }
+}
+
+/// Map `items` in order, spreading them over up to `threads` threads
+fn parallel_map<T: Sync, R: Send>(
+    items: &[T],
+    threads: usize,
+    f: impl Fn(&T) -> R + Sync,
+) -> Vec<R> {
+    if threads <= 1 || items.len() <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let chunk_size = items.len().div_ceil(threads);
+    thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(|| chunk.iter().map(&f).collect::<Vec<_>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| match handle.join() {
+                Ok(results) => results,
+                Err(panic) => std::panic::resume_unwind(panic),
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use super::*;
+
+    /// A suggestion to replace `range` of `source`, which must start within its first line
+    fn suggestion(source: &str, range: Range<usize>, replacement: &str) -> Suggestion {
+        let line_start = source[..range.start].matches('\n').count() + 1;
+        let line_end = source[..range.end].matches('\n').count() + 1;
+        let line_offset = source[..range.start]
+            .rfind('\n')
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let text = source[line_offset..].lines().next().unwrap_or_default();
+        let column_start = range.start - line_offset + 1;
+        let column_end = range.end - line_offset + 1;
+        let span = |replacement: Option<&str>| {
+            serde_json::json!({
+                "file_name": "src/lib.rs",
+                "byte_start": range.start,
+                "byte_end": range.end,
+                "line_start": line_start,
+                "line_end": line_end,
+                "column_start": column_start,
+                "column_end": column_end,
+                "is_primary": true,
+                "text": [{
+                    "text": text,
+                    "highlight_start": column_start,
+                    "highlight_end": column_end,
+                }],
+                "label": null,
+                "suggested_replacement": replacement,
+                "suggestion_applicability": replacement.map(|_| "MachineApplicable"),
+                "expansion": null,
+            })
+        };
+        let diagnostic = serde_json::json!({
+            "message": "unused",
+            "code": null,
+            "level": "warning",
+            "spans": [span(None)],
+            "children": [{
+                "message": "remove it",
+                "code": null,
+                "level": "help",
+                "spans": [span(Some(replacement))],
+                "children": [],
+                "rendered": null,
+            }],
+            "rendered": null,
+        });
+        rustfix::get_suggestions_from_json(
+            &diagnostic.to_string(),
+            &HashSet::new(),
+            rustfix::Filter::Everything,
+        )
+        .unwrap()
+        .remove(0)
+    }
+
+    /// `source` with a suggestion to remove all of it but the last newline, applied first, and
+    /// one to replace the first character of each of `lines`, which overlap it
+    fn overlapping(source: &str, lines: &[usize]) -> IndexSet<(Suggestion, Option<Remaining>)> {
+        let mut suggestions = IndexSet::new();
+        for line in lines {
+            let start = source
+                .split_inclusive('\n')
+                .take(line - 1)
+                .map(str::len)
+                .sum::<usize>();
+            suggestions.insert((suggestion(source, start..start + 1, "x"), None));
+        }
+        // Suggestions are applied last to first
+        suggestions.insert((suggestion(source, 0..source.len() - 1, ""), None));
+        suggestions
+    }
+
+    #[test]
+    fn apply_failures_are_aggregated() {
+        let source = "a\nb\nc\nd\ne\nf\ng\nh\ni\n";
+        let suggestions = overlapping(source, &[2, 3, 4, 5, 6, 7, 8]);
+
+        let fixed = apply_suggestions(source.to_owned(), &suggestions).unwrap();
+        assert_eq!(fixed.applied.len(), 1);
+        assert_eq!(fixed.failed.len(), 7);
+        assert_eq!(fixed.failures.len(), 1);
+        assert_eq!(
+            fixed.failures[0].message("src/lib.rs"),
+            "failed to apply 7 suggestions to `src/lib.rs` at lines 2, 3, 4, 5, 6 and 2 more as \
+            they overlap with other ones"
+        );
+    }
+
+    #[test]
+    fn apply_failure() {
+        let source = "a\nb\nc\n";
+        let suggestions = overlapping(source, &[2]);
+
+        let fixed = apply_suggestions(source.to_owned(), &suggestions).unwrap();
+        assert_eq!(fixed.failures.len(), 1);
+        assert_eq!(
+            fixed.failures[0].message("src/lib.rs"),
+            "failed to apply a suggestion to `src/lib.rs` at line 2 as it overlaps with another one"
+        );
+    }
+}
@@ -1,2 +1,5 @@
 pub mod check;
 pub mod fixit;
+pub mod review;
+pub mod scheduler;
+pub mod summary;
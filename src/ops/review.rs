@@ -0,0 +1,78 @@
+//! Asking which suggestions to apply, for `--interactive`
+
+use std::collections::HashSet;
+
+use rustfix::{CodeFix, Suggestion};
+
+use crate::{core::shell, util::diff::unified_diff, CargoResult};
+
+/// The answers given so far, which carry over to later checks
+#[derive(Debug, Default)]
+pub struct Review {
+    /// Lints whose suggestions are applied without asking
+    accepted_lints: HashSet<String>,
+    /// Suggestions that were declined, so they aren't asked about again when re-reported
+    declined: HashSet<Suggestion>,
+    /// Whether the rest of the suggestions are declined without asking
+    quit: bool,
+}
+
+impl Review {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show a suggestion for `file`, whose contents are `source`, and ask whether to apply it
+    ///
+    /// Running out of input is taken as quitting.
+    pub fn accept(
+        &mut self,
+        suggestion: &Suggestion,
+        lint: Option<&str>,
+        rendered: Option<&str>,
+        file: &str,
+        source: &str,
+    ) -> CargoResult<bool> {
+        if self.quit || self.declined.contains(suggestion) {
+            return Ok(false);
+        }
+        if lint.is_some_and(|l| self.accepted_lints.contains(l)) {
+            return Ok(true);
+        }
+
+        if let Some(rendered) = rendered {
+            shell::print_ansi_stderr(format!("{}\n\n", rendered.trim_end()).as_bytes())?;
+        }
+        let mut fixed = CodeFix::new(source);
+        if fixed.apply(suggestion).is_ok() {
+            shell::print_diff_stderr(&unified_diff(source, &fixed.finish()?, file))?;
+        }
+
+        let question = match lint {
+            Some(lint) => format!("apply this fix? [y]es, [n]o, [a]ll `{lint}` fixes, [q]uit"),
+            None => "apply this fix? [y]es, [n]o, [q]uit".to_owned(),
+        };
+        loop {
+            let Some(answer) = shell::prompt(&question)? else {
+                self.quit = true;
+                return Ok(false);
+            };
+            match (answer.trim(), lint) {
+                ("y" | "yes", _) => return Ok(true),
+                ("n" | "no", _) => {
+                    self.declined.insert(suggestion.clone());
+                    return Ok(false);
+                }
+                ("a" | "all", Some(lint)) => {
+                    self.accepted_lints.insert(lint.to_owned());
+                    return Ok(true);
+                }
+                ("q" | "quit", _) => {
+                    self.quit = true;
+                    return Ok(false);
+                }
+                _ => {}
+            }
+        }
+    }
+}
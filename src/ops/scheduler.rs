@@ -0,0 +1,227 @@
+//! Order in which build units are fixed
+
+use std::collections::HashSet;
+
+use crate::ops::{check::BuildUnit, fixit::TargetKind};
+
+/// Tracks which build unit is being fixed and which are done
+///
+/// Units are fixed one at a time. The current unit is re-checked until a run makes no more
+/// changes, or `max_iterations` runs have been made, and is then done for the rest of the run.
+/// Units with nothing to fix are done as soon as they are seen.
+#[derive(Debug)]
+pub struct Scheduler {
+    current: Option<BuildUnit>,
+    done: HashSet<BuildUnit>,
+    /// Every unit reported by cargo so far
+    units: HashSet<BuildUnit>,
+    iteration: usize,
+    max_iterations: usize,
+}
+
+impl Scheduler {
+    pub fn new(max_iterations: usize) -> Self {
+        Self {
+            current: None,
+            done: HashSet::new(),
+            units: HashSet::new(),
+            iteration: 0,
+            max_iterations,
+        }
+    }
+
+    /// The unit being fixed, if any
+    pub fn current(&self) -> Option<&BuildUnit> {
+        self.current.as_ref()
+    }
+
+    pub fn iteration(&self) -> usize {
+        self.iteration
+    }
+
+    /// The units that are done, whose messages should be ignored
+    pub fn done(&self) -> &HashSet<BuildUnit> {
+        &self.done
+    }
+
+    /// Every unit reported by cargo so far, including ones a run stopped before reaching
+    pub fn units(&self) -> &HashSet<BuildUnit> {
+        &self.units
+    }
+
+    pub fn is_done(&self, unit: &BuildUnit) -> bool {
+        self.done.contains(unit)
+    }
+
+    /// Whether no unit of `package_id` is done yet, i.e. the package hasn't been reported
+    pub fn is_new_package(&self, package_id: &str) -> bool {
+        self.done.iter().all(|b| b.package_id != package_id)
+    }
+
+    /// Whether the current unit has used up its runs
+    pub fn exhausted(&self) -> bool {
+        self.iteration >= self.max_iterations
+    }
+
+    /// Record the units reported by a run
+    pub fn observe<'a>(&mut self, units: impl IntoIterator<Item = &'a BuildUnit>) {
+        self.units.extend(units.into_iter().cloned());
+    }
+
+    /// Make `unit` the current unit if there is none, returning whether it is the current unit
+    pub fn select(&mut self, unit: &BuildUnit) -> bool {
+        self.current.get_or_insert_with(|| unit.clone()) == unit
+    }
+
+    /// Mark `unit` as done without making it current, as it has nothing to fix
+    pub fn skip(&mut self, unit: BuildUnit) {
+        self.done.insert(unit);
+    }
+
+    /// Count a run against the current unit
+    pub fn next_iteration(&mut self) {
+        self.iteration += 1;
+    }
+
+    /// Mark the current unit as done, returning it
+    ///
+    /// Returns `None` if there is no current unit, meaning every unit is done.
+    pub fn finish(&mut self) -> Option<BuildUnit> {
+        let unit = self.current.take()?;
+        self.done.insert(unit.clone());
+        self.iteration = 0;
+        Some(unit)
+    }
+
+    /// Where the current unit is among the binaries or examples of its package, e.g.
+    /// `bin "server" 2/9`
+    ///
+    /// Packages with a single target of the kind are already identified by their status line.
+    pub fn progress(&self) -> Option<String> {
+        let target = self.current.as_ref()?;
+        let kind = [TargetKind::Bin, TargetKind::Example]
+            .into_iter()
+            .find(|k| k.matches(&target.target))?;
+        let same = |b: &&BuildUnit| b.package_id == target.package_id && kind.matches(&b.target);
+        let total = self.units.iter().filter(same).count();
+        if total < 2 {
+            return None;
+        }
+        let done = self.done.iter().filter(same).count();
+        Some(format!(
+            "{} \"{}\" {}/{total}",
+            kind.name(),
+            target.target.name(),
+            done + 1
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(package: &str, kind: &str, name: &str) -> BuildUnit {
+        serde_json::from_value(serde_json::json!({
+            "package_id": package,
+            "target": {
+                "kind": [kind],
+                "crate_types": [if kind == "lib" { "lib" } else { "bin" }],
+                "name": name,
+                "src_path": format!("/{package}/src/{name}.rs"),
+                "edition": "2021",
+                "doc": true,
+                "doctest": false,
+                "test": true,
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn select_keeps_the_first_unit() {
+        let lib = unit("foo", "lib", "foo");
+        let bin = unit("foo", "bin", "foo");
+        let mut scheduler = Scheduler::new(4);
+
+        assert_eq!(scheduler.current(), None);
+        assert!(scheduler.select(&lib));
+        assert!(!scheduler.select(&bin));
+        assert!(scheduler.select(&lib));
+        assert_eq!(scheduler.current(), Some(&lib));
+    }
+
+    #[test]
+    fn skip_marks_done_without_selecting() {
+        let lib = unit("foo", "lib", "foo");
+        let mut scheduler = Scheduler::new(4);
+
+        assert!(scheduler.is_new_package("foo"));
+        scheduler.skip(lib.clone());
+        assert!(scheduler.is_done(&lib));
+        assert!(!scheduler.is_new_package("foo"));
+        assert!(scheduler.is_new_package("bar"));
+        assert_eq!(scheduler.current(), None);
+    }
+
+    #[test]
+    fn finish_moves_to_the_next_unit() {
+        let lib = unit("foo", "lib", "foo");
+        let bin = unit("foo", "bin", "foo");
+        let mut scheduler = Scheduler::new(4);
+
+        assert!(scheduler.select(&lib));
+        scheduler.next_iteration();
+        assert_eq!(scheduler.iteration(), 1);
+        assert_eq!(scheduler.finish(), Some(lib.clone()));
+        assert!(scheduler.is_done(&lib));
+        assert_eq!(scheduler.iteration(), 0);
+
+        assert!(scheduler.select(&bin));
+        assert_eq!(scheduler.finish(), Some(bin.clone()));
+        assert_eq!(scheduler.finish(), None);
+        assert_eq!(scheduler.done().len(), 2);
+    }
+
+    #[test]
+    fn exhausted_after_max_iterations() {
+        let lib = unit("foo", "lib", "foo");
+        let mut scheduler = Scheduler::new(2);
+
+        assert!(scheduler.select(&lib));
+        assert!(!scheduler.exhausted());
+        scheduler.next_iteration();
+        assert!(!scheduler.exhausted());
+        scheduler.next_iteration();
+        assert!(scheduler.exhausted());
+
+        scheduler.finish();
+        assert!(!scheduler.exhausted());
+    }
+
+    #[test]
+    fn progress_counts_targets_of_the_same_kind() {
+        let lib = unit("foo", "lib", "foo");
+        let server = unit("foo", "bin", "server");
+        let client = unit("foo", "bin", "client");
+        let other = unit("bar", "bin", "other");
+        let mut scheduler = Scheduler::new(4);
+        scheduler.observe([&lib, &server, &client, &other]);
+
+        assert_eq!(scheduler.progress(), None);
+        scheduler.select(&lib);
+        assert_eq!(scheduler.progress(), None);
+        scheduler.finish();
+
+        scheduler.select(&server);
+        assert_eq!(scheduler.progress().as_deref(), Some("bin \"server\" 1/2"));
+        scheduler.finish();
+
+        scheduler.select(&client);
+        assert_eq!(scheduler.progress().as_deref(), Some("bin \"client\" 2/2"));
+        scheduler.finish();
+
+        scheduler.select(&other);
+        assert_eq!(scheduler.progress(), None);
+    }
+}
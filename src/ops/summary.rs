@@ -0,0 +1,409 @@
+//! Outcome of a `cargo fixit` run
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+    path::Path,
+};
+
+use cargo_util::paths;
+use indexmap::IndexSet;
+use rustfix::Suggestion;
+use serde::Serialize;
+
+use crate::{
+    core::{
+        rustc::get_rustc_version,
+        shell::{self, Annotation, Location},
+        workspace::get_workspace,
+    },
+    ops::fixit::{Remaining, SkipReason},
+    util::run_id::new_run_id,
+    CargoResult,
+};
+
+/// Everything reported during a run, for writing out as a single JSON document
+#[derive(Debug, Serialize)]
+pub struct Summary {
+    /// Identifies this run across outputs
+    pub run_id: String,
+    /// Version of cargo-fixit
+    pub version: &'static str,
+    /// Version of the active `rustc`, if it could be determined
+    pub rustc: Option<String>,
+    pub fixed: Vec<FixedFile>,
+    /// Diagnostics left over after fixing, as rendered by rustc
+    pub remaining: Vec<String>,
+    pub skipped: Vec<SkippedFile>,
+    /// Packages that were checked and had nothing to fix
+    pub clean: Vec<String>,
+    /// Packages with code that will be rejected by a future version of Rust, if
+    /// `--future-incompat-report` was passed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub future_incompat: Option<FutureIncompat>,
+    /// The limit on checks per target, if a target reached it with fixes left
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<usize>,
+    /// Whether `fixed` lists the fixes that would have been applied, with `--dry-run`
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
+    /// The cargo commands that were run, if `--print-command` was passed
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub commands: Vec<String>,
+    /// Environment variables kept from cargo, by `--hermetic-env` or as they conflict with how
+    /// cargo-fixit runs it
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub removed_env: Vec<String>,
+    /// Kept out of the summary as it is written separately, without any paths or code
+    #[serde(skip)]
+    pub lints: BTreeMap<String, LintStats>,
+    /// Kept out of the summary as it is written separately
+    #[serde(skip)]
+    pub skipped_suggestions: IndexSet<SkippedSuggestion>,
+    /// The diagnostics behind `remaining`, for `--output-format`
+    #[serde(skip)]
+    pub(crate) diagnostics: IndexSet<Remaining>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FixedFile {
+    pub file: String,
+    pub fixes: u32,
+}
+
+/// How often a lint was fixed or left for the user
+#[derive(Debug, Default, Serialize)]
+pub struct LintStats {
+    pub fixed: u32,
+    pub skipped: u32,
+}
+
+/// Packages with code that will be rejected by a future version of Rust, e.g. `bar v0.1.0`
+#[derive(Debug, Default, Serialize)]
+pub struct FutureIncompat {
+    pub workspace: Vec<String>,
+    pub dependencies: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SkippedFile {
+    pub file: String,
+    pub reason: SkipReason,
+}
+
+/// A suggestion that was deliberately not applied
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct SkippedSuggestion {
+    pub file: String,
+    pub line_start: usize,
+    pub column_start: usize,
+    pub line_end: usize,
+    pub column_end: usize,
+    pub lint: Option<String>,
+    pub reason: SkipReason,
+}
+
+impl Summary {
+    pub fn new() -> Self {
+        Self {
+            run_id: new_run_id(),
+            version: env!("CARGO_PKG_VERSION"),
+            rustc: get_rustc_version().as_ref().map(ToString::to_string),
+            fixed: Vec::new(),
+            remaining: Vec::new(),
+            skipped: Vec::new(),
+            clean: Vec::new(),
+            future_incompat: None,
+            max_retries: None,
+            dry_run: false,
+            commands: Vec::new(),
+            removed_env: Vec::new(),
+            lints: BTreeMap::new(),
+            skipped_suggestions: IndexSet::new(),
+            diagnostics: IndexSet::new(),
+        }
+    }
+
+    /// Report the fixes applied to a file, given the lint behind each of them
+    pub(crate) fn fixed<'a>(
+        &mut self,
+        file: String,
+        lints: impl ExactSizeIterator<Item = Option<&'a str>>,
+    ) -> CargoResult<()> {
+        let fixes = lints.len() as u32;
+        let mut names = BTreeSet::new();
+        for lint in lints.flatten() {
+            self.lint(lint).fixed += 1;
+            names.insert(lint);
+        }
+        if self.dry_run {
+            let names = names.into_iter().collect::<Vec<_>>().join(", ");
+            shell::status(
+                "Fixable",
+                format!(
+                    "{file} ({fixes} {}{}{names})",
+                    if fixes == 1 { "fix" } else { "fixes" },
+                    if names.is_empty() { "" } else { ": " },
+                ),
+            )?;
+        } else {
+            shell::fixed(&file, fixes, &names.into_iter().collect::<Vec<_>>())?;
+        }
+        self.fixed.push(FixedFile { file, fixes });
+        Ok(())
+    }
+
+    /// Report the packages cargo found code that will be rejected by a future version of Rust in,
+    /// split into workspace members and dependencies
+    pub(crate) fn future_incompat(&mut self, packages: Vec<String>) -> CargoResult<()> {
+        let members: Vec<_> = get_workspace()
+            .iter()
+            .flat_map(|w| &w.packages)
+            .map(|p| p.name.as_str())
+            .collect();
+        let mut report = FutureIncompat::default();
+        for package in packages {
+            let name = package.split_whitespace().next().unwrap_or_default();
+            if members.contains(&name) {
+                report.workspace.push(package);
+            } else {
+                report.dependencies.push(package);
+            }
+        }
+
+        if !report.workspace.is_empty() || !report.dependencies.is_empty() {
+            shell::warn(
+                "the following packages contain code that will be rejected by a future version \
+                of Rust",
+            )?;
+            if !report.workspace.is_empty() {
+                shell::note(format!("in the workspace: {}", report.workspace.join(", ")))?;
+            }
+            if !report.dependencies.is_empty() {
+                shell::note(format!(
+                    "in dependencies: {}",
+                    report.dependencies.join(", ")
+                ))?;
+            }
+            shell::note("run `cargo report future-incompatibilities` for the details")?;
+        }
+        self.future_incompat = Some(report);
+        Ok(())
+    }
+
+    /// Report a diagnostic that was not fixed
+    ///
+    /// A diagnostic in a file shared between units, e.g. a module of several integration tests,
+    /// is reported again by the units checked later. Like cargo does within a run, it is only
+    /// reported and counted the first time.
+    pub(crate) fn diagnostic(&mut self, diagnostic: &Remaining) -> CargoResult<()> {
+        if !self.diagnostics.insert(diagnostic.clone()) {
+            return Ok(());
+        }
+        if let Some(lint) = &diagnostic.lint {
+            self.lint(lint).skipped += 1;
+        }
+        let rendered = diagnostic.rendered.trim_end();
+        let annotation = Annotation {
+            level: &diagnostic.level,
+            lint: diagnostic.lint.as_deref(),
+            message: &diagnostic.message,
+            location: diagnostic.span.as_ref().map(|span| Location {
+                file: &span.file,
+                line_start: span.line_start,
+                column_start: span.column_start,
+                line_end: span.line_end,
+                column_end: span.column_end,
+            }),
+        };
+        shell::remaining(format!("{rendered}\n\n").as_bytes(), &annotation)?;
+        self.remaining
+            .push(anstream::adapter::strip_str(rendered).to_string());
+        Ok(())
+    }
+
+    /// Record that the suggestions for a file were not applied, given the lint behind each of
+    /// them
+    pub(crate) fn skipped<'a>(
+        &mut self,
+        file: String,
+        reason: SkipReason,
+        suggestions: impl Iterator<Item = (&'a Suggestion, Option<&'a str>)>,
+    ) -> CargoResult<()> {
+        self.unapplied(&file, reason, suggestions);
+        let name = serde_json::to_value(reason)?;
+        shell::skipped(&file, name.as_str().unwrap_or_default())?;
+        self.skipped.push(SkippedFile { file, reason });
+        Ok(())
+    }
+
+    /// Record that some suggestions for a file were not applied, without skipping the rest of
+    /// the file, given the lint behind each of them
+    pub(crate) fn unapplied<'a>(
+        &mut self,
+        file: &str,
+        reason: SkipReason,
+        suggestions: impl Iterator<Item = (&'a Suggestion, Option<&'a str>)>,
+    ) {
+        for (suggestion, lint) in suggestions {
+            let Some(snippet) = suggestion.snippets.first() else {
+                continue;
+            };
+            self.skipped_suggestions.insert(SkippedSuggestion {
+                file: file.to_owned(),
+                line_start: snippet.line_range.start.line,
+                column_start: snippet.line_range.start.column,
+                line_end: snippet.line_range.end.line,
+                column_end: snippet.line_range.end.column,
+                lint: lint.map(ToOwned::to_owned),
+                reason,
+            });
+        }
+    }
+
+    fn lint(&mut self, lint: &str) -> &mut LintStats {
+        self.lints.entry(lint.to_owned()).or_default()
+    }
+
+    /// Report how often each lint was fixed and left for the user, e.g. as a table
+    pub fn print_stats(&self) -> CargoResult<()> {
+        if self.lints.is_empty() {
+            return Ok(());
+        }
+        let fixed = if self.dry_run { "Fixable" } else { "Fixed" };
+        let width = self.lints.keys().map(String::len).max().unwrap_or_default();
+        let width = width.max("Lint".len());
+        let mut out = format!("{:width$}  {fixed:>7}  Remaining\n", "Lint");
+        for (lint, stats) in &self.lints {
+            out.push_str(&format!(
+                "{lint:width$}  {:>7}  {:>9}\n",
+                stats.fixed, stats.skipped
+            ));
+        }
+        shell::stats(&out, &serde_json::to_value(&self.lints)?)
+    }
+
+    /// Report the summary through the renderer, e.g. as the last `--message-format json` event
+    pub fn report(&self) -> CargoResult<()> {
+        shell::summary(&serde_json::to_value(self)?)
+    }
+
+    /// Write the summary as JSON to `path`, or stdout for `-`
+    pub fn write(&self, path: &Path) -> CargoResult<()> {
+        write_json(path, self)
+    }
+
+    /// Write the skipped suggestions as JSON to `path`, or stdout for `-`
+    pub fn write_skip_ledger(&self, path: &Path) -> CargoResult<()> {
+        write_json(
+            path,
+            &serde_json::json!({
+                "run_id": self.run_id,
+                "skipped": self.skipped_suggestions.iter().collect::<Vec<_>>(),
+            }),
+        )
+    }
+
+    /// Write the per-lint counts as JSON to `path`, or stdout for `-`
+    pub fn write_lint_stats(&self, path: &Path) -> CargoResult<()> {
+        write_json(
+            path,
+            &serde_json::json!({
+                "version": self.version,
+                "lints": self.lints,
+            }),
+        )
+    }
+
+    /// Write the remaining diagnostics as SARIF to `path`, or stdout for `-`
+    pub fn write_sarif(&self, path: &Path) -> CargoResult<()> {
+        let rules = self
+            .diagnostics
+            .iter()
+            .filter_map(|d| d.lint.as_deref())
+            .collect::<BTreeSet<_>>();
+        let results = self
+            .diagnostics
+            .iter()
+            .map(|d| {
+                let level = match d.level.as_str() {
+                    l if l.starts_with("error") => "error",
+                    "warning" => "warning",
+                    _ => "note",
+                };
+                let locations = d
+                    .span
+                    .iter()
+                    .map(|span| {
+                        serde_json::json!({
+                            "physicalLocation": {
+                                "artifactLocation": {
+                                    "uri": span.file.replace('\\', "/"),
+                                    "uriBaseId": "%SRCROOT%",
+                                },
+                                "region": {
+                                    "startLine": span.line_start,
+                                    "startColumn": span.column_start,
+                                    "endLine": span.line_end,
+                                    "endColumn": span.column_end,
+                                },
+                            },
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                let mut result = serde_json::json!({
+                    "level": level,
+                    "message": { "text": d.message },
+                    "locations": locations,
+                });
+                if let (Some(lint), Some(result)) = (&d.lint, result.as_object_mut()) {
+                    result.insert("ruleId".to_owned(), lint.as_str().into());
+                }
+                result
+            })
+            .collect::<Vec<_>>();
+
+        write_json(
+            path,
+            &serde_json::json!({
+                "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+                "version": "2.1.0",
+                "runs": [{
+                    "tool": {
+                        "driver": {
+                            "name": "cargo-fixit",
+                            "version": self.version,
+                            "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                            "rules": rules
+                                .into_iter()
+                                .map(|id| serde_json::json!({ "id": id }))
+                                .collect::<Vec<_>>(),
+                        },
+                    },
+                    "automationDetails": { "id": self.run_id },
+                    // rustc counts columns in characters
+                    "columnKind": "unicodeCodePoints",
+                    "results": results,
+                }],
+            }),
+        )
+    }
+}
+
+fn write_json(path: &Path, value: &impl Serialize) -> CargoResult<()> {
+    let mut json = serde_json::to_string_pretty(value)?;
+    json.push('\n');
+    if path == Path::new("-") {
+        let mut stdout = std::io::stdout().lock();
+        stdout.write_all(json.as_bytes())?;
+    } else {
+        paths::write(path, json)?;
+    }
+    Ok(())
+}
+
+impl Default for Summary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
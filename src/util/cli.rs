@@ -1,5 +1,14 @@
+//! Package, target, and feature selection flags for commands that wrap `cargo check`
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
 use clap::Parser;
 
+use crate::core::config::Config;
+
 #[derive(Debug, Parser)]
 pub struct CheckFlags {
     /// Package(s) to fix
@@ -28,7 +37,7 @@ pub struct CheckFlags {
 
     /// Fix only the specified binary
     #[arg(long, value_name = "NAME", help_heading = "Target Selection")]
-    bin: Option<String>,
+    bin: Vec<String>,
 
     /// Fix all examples
     #[arg(long, help_heading = "Target Selection")]
@@ -36,7 +45,7 @@ pub struct CheckFlags {
 
     /// Fix only the specified binary
     #[arg(long, value_name = "NAME", help_heading = "Target Selection")]
-    example: Option<String>,
+    example: Vec<String>,
 
     /// Fix all tests
     #[arg(long, help_heading = "Target Selection")]
@@ -44,7 +53,7 @@ pub struct CheckFlags {
 
     /// Fix only the specified test
     #[arg(long, value_name = "NAME", help_heading = "Target Selection")]
-    test: Option<String>,
+    test: Vec<String>,
 
     /// Fix all benches
     #[arg(long, help_heading = "Target Selection")]
@@ -52,7 +61,7 @@ pub struct CheckFlags {
 
     /// Fix only the specified bench
     #[arg(long, value_name = "NAME", help_heading = "Target Selection")]
-    bench: Option<String>,
+    bench: Vec<String>,
 
     /// Fix all targets
     #[arg(long, help_heading = "Target Selection")]
@@ -75,12 +84,67 @@ pub struct CheckFlags {
     #[arg(long, help_heading = "Feature Selection")]
     no_default_features: bool,
 
+    /// Fix artifacts in release mode, with optimizations
+    #[arg(short, long, help_heading = "Compilation Options")]
+    release: bool,
+
+    /// Fix artifacts with the specified profile
+    #[arg(
+        long,
+        value_name = "PROFILE-NAME",
+        help_heading = "Compilation Options"
+    )]
+    profile: Option<String>,
+
+    /// Number of parallel jobs, defaults to # of CPUs
+    #[arg(
+        short,
+        long,
+        value_name = "N",
+        allow_negative_numbers = true,
+        help_heading = "Compilation Options"
+    )]
+    jobs: Option<String>,
+
+    /// Do not abort the build as soon as there is an error
+    #[arg(long, help_heading = "Compilation Options")]
+    keep_going: bool,
+
+    /// Directory for all generated artifacts
+    #[arg(long, value_name = "DIRECTORY", help_heading = "Compilation Options")]
+    target_dir: Option<String>,
+
+    /// Assert that `Cargo.lock` will remain unchanged
+    #[arg(long, help_heading = "Manifest Options")]
+    locked: bool,
+
+    /// Run without accessing the network
+    #[arg(long, help_heading = "Manifest Options")]
+    offline: bool,
+
+    /// Equivalent to specifying both --locked and --offline
+    #[arg(long, help_heading = "Manifest Options")]
+    frozen: bool,
+
+    /// Ignore `rust-version` specification in packages
+    #[arg(long, help_heading = "Manifest Options")]
+    ignore_rust_version: bool,
+
+    /// Path to Cargo.lock, e.g. outside of a read-only checkout (unstable)
+    #[arg(long, value_name = "PATH", help_heading = "Manifest Options")]
+    lockfile_path: Option<PathBuf>,
+
+    /// Override a configuration value
+    #[arg(long, value_name = "KEY=VALUE")]
+    config: Vec<String>,
+
     /// Unstable (nightly-only) flags
     #[arg(short = 'Z', value_name = "FLAG")]
     unstable_flags: Vec<String>,
 }
 
 impl CheckFlags {
+    /// The arguments to forward to cargo
     pub fn to_flags(&self) -> Vec<String> {
         let mut out = Vec::new();
 
@@ -106,7 +170,7 @@ impl CheckFlags {
         if self.bins {
             out.push("--bins".to_owned());
         }
-        if let Some(b) = self.bin.clone() {
+        for b in self.bin.clone() {
             out.push("--bin".to_owned());
             out.push(b);
         }
@@ -114,7 +178,7 @@ impl CheckFlags {
         if self.examples {
             out.push("--examples".to_owned());
         }
-        if let Some(b) = self.example.clone() {
+        for b in self.example.clone() {
             out.push("--example".to_owned());
             out.push(b);
         }
@@ -122,7 +186,7 @@ impl CheckFlags {
         if self.tests {
             out.push("--tests".to_owned());
         }
-        if let Some(b) = self.test.clone() {
+        for b in self.test.clone() {
             out.push("--test".to_owned());
             out.push(b);
         }
@@ -130,7 +194,7 @@ impl CheckFlags {
         if self.benches {
             out.push("--benches".to_owned());
         }
-        if let Some(b) = self.bench.clone() {
+        for b in self.bench.clone() {
             out.push("--bench".to_owned());
             out.push(b);
         }
@@ -150,6 +214,53 @@ impl CheckFlags {
             out.push("--no-default-features".to_owned());
         }
 
+        if self.release {
+            out.push("--release".to_owned());
+        }
+        if let Some(p) = self.profile.clone() {
+            out.push("--profile".to_owned());
+            out.push(p);
+        }
+        if let Some(j) = self.jobs.clone() {
+            out.push("--jobs".to_owned());
+            out.push(j);
+        }
+        if self.keep_going {
+            out.push("--keep-going".to_owned());
+        }
+        if let Some(d) = self.target_dir.clone() {
+            out.push("--target-dir".to_owned());
+            out.push(d);
+        }
+
+        if self.locked {
+            out.push("--locked".to_owned());
+        }
+        if self.offline {
+            out.push("--offline".to_owned());
+        }
+        if self.frozen {
+            out.push("--frozen".to_owned());
+        }
+        if self.ignore_rust_version {
+            out.push("--ignore-rust-version".to_owned());
+        }
+        if let Some(p) = &self.lockfile_path {
+            // Cargo only takes this as configuration, behind `-Zlockfile-path`
+            let path = serde_json::to_string(&p.display().to_string()).unwrap_or_default();
+            out.push("--config".to_owned());
+            out.push(format!("resolver.lockfile-path={path}"));
+            if !self.unstable_flags.iter().any(|f| f == "lockfile-path") {
+                out.push("-Z".to_owned());
+                out.push("lockfile-path".to_owned());
+            }
+        }
+
+        for i in self.config.clone() {
+            out.push("--config".to_owned());
+            out.push(i);
+        }
+
         for i in self.unstable_flags.clone() {
             out.push("-Z".to_owned());
             out.push(i);
@@ -157,4 +268,35 @@ impl CheckFlags {
 
         out
     }
+
+    /// The directory passed with `--target-dir`, if any
+    pub fn target_dir(&self) -> Option<&Path> {
+        self.target_dir.as_deref().map(Path::new)
+    }
+
+    /// Select all targets, unless some are already selected
+    pub fn default_to_all_targets(&mut self) {
+        let selected = self.lib
+            || self.bins
+            || !self.bin.is_empty()
+            || self.examples
+            || !self.example.is_empty()
+            || self.tests
+            || !self.test.is_empty()
+            || self.benches
+            || !self.bench.is_empty();
+        if !selected {
+            self.all_targets = true;
+        }
+    }
+
+    /// The `Cargo.lock` passed with `--lockfile-path`, if any
+    pub fn lockfile_path(&self) -> Option<&Path> {
+        self.lockfile_path.as_deref()
+    }
+
+    /// Cargo's configuration in the current directory, with the `--config` overrides
+    pub fn cargo_config(&self) -> Config {
+        Config::load(&env::current_dir().unwrap_or_default(), &self.config)
+    }
 }
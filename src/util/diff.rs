@@ -0,0 +1,149 @@
+//! Unified diffs of the fixes applied to a file
+
+/// Lines of unchanged context shown around each change
+const CONTEXT: usize = 3;
+
+/// One step of turning the old lines into the new ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit {
+    /// A line in both, by its index in the old and the new lines
+    Keep(usize, usize),
+    /// A line only in the old lines
+    Delete(usize),
+    /// A line only in the new lines
+    Insert(usize),
+}
+
+/// A unified diff from `old` to `new`, as accepted by `git apply`, or an empty string if they
+/// are the same
+pub fn unified_diff(old: &str, new: &str, path: &str) -> String {
+    let old: Vec<_> = old.split_inclusive('\n').collect();
+    let new: Vec<_> = new.split_inclusive('\n').collect();
+    let edits = edits(&old, &new);
+    let is_change = |e: &Edit| !matches!(e, Edit::Keep(..));
+
+    let mut out = String::new();
+    let mut next = 0;
+    while let Some(first) = edits[next..].iter().position(is_change) {
+        let first = next + first;
+        // Changes close enough for their context to overlap go in the same hunk
+        let mut end = first + 1;
+        while let Some(change) = edits[end..].iter().position(is_change) {
+            if change > 2 * CONTEXT {
+                break;
+            }
+            end += change + 1;
+        }
+        let start = first.saturating_sub(CONTEXT);
+        let end = (end + CONTEXT).min(edits.len());
+
+        let before = &edits[..start];
+        let hunk = &edits[start..end];
+        let old_before = before
+            .iter()
+            .filter(|e| !matches!(e, Edit::Insert(_)))
+            .count();
+        let new_before = before
+            .iter()
+            .filter(|e| !matches!(e, Edit::Delete(_)))
+            .count();
+        let old_len = hunk
+            .iter()
+            .filter(|e| !matches!(e, Edit::Insert(_)))
+            .count();
+        let new_len = hunk
+            .iter()
+            .filter(|e| !matches!(e, Edit::Delete(_)))
+            .count();
+        // Empty ranges start at the line before them
+        let first_line = |before: usize, len: usize| if len == 0 { before } else { before + 1 };
+
+        if out.is_empty() {
+            out.push_str(&format!("--- a/{path}\n+++ b/{path}\n"));
+        }
+        out.push_str(&format!(
+            "@@ -{},{old_len} +{},{new_len} @@\n",
+            first_line(old_before, old_len),
+            first_line(new_before, new_len)
+        ));
+        for edit in hunk {
+            let (prefix, line) = match *edit {
+                Edit::Keep(i, _) => (' ', old[i]),
+                Edit::Delete(i) => ('-', old[i]),
+                Edit::Insert(i) => ('+', new[i]),
+            };
+            out.push(prefix);
+            out.push_str(line);
+            if !line.ends_with('\n') {
+                out.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+
+        next = end;
+    }
+    out
+}
+
+/// The shortest edits turning `old` into `new`, using Myers' algorithm
+fn edits(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    // The furthest `x` reached on each diagonal `k = x - y` after `d` edits, indexed by `k + d`
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+    'search: for d in 0..=n + m {
+        let mut v = vec![0; 2 * d as usize + 1];
+        for k in (-d..=d).step_by(2) {
+            let prev = |k: isize| trace[d as usize - 1][(k + d - 1) as usize];
+            let mut x = if d == 0 {
+                0
+            } else if k == -d || (k != d && prev(k - 1) < prev(k + 1)) {
+                prev(k + 1)
+            } else {
+                prev(k - 1) + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[(k + d) as usize] = x;
+            if x >= n && y >= m {
+                trace.push(v);
+                break 'search;
+            }
+        }
+        trace.push(v);
+    }
+
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for d in (1..trace.len() as isize).rev() {
+        let prev = &trace[d as usize - 1];
+        let at = |k: isize| prev[(k + d - 1) as usize];
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = at(prev_k);
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            edits.push(Edit::Keep(x as usize, y as usize));
+        }
+        if x == prev_x {
+            edits.push(Edit::Insert(prev_y as usize));
+        } else {
+            edits.push(Edit::Delete(prev_x as usize));
+        }
+        (x, y) = (prev_x, prev_y);
+    }
+    while x > 0 && y > 0 {
+        x -= 1;
+        y -= 1;
+        edits.push(Edit::Keep(x as usize, y as usize));
+    }
+    edits.reverse();
+    edits
+}
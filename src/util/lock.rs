@@ -0,0 +1,84 @@
+use std::{
+    fs::{File, OpenOptions, TryLockError},
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
+
+use cargo_util::paths;
+use tracing::warn;
+
+use crate::{
+    core::{shell, workspace::get_workspace},
+    CargoResult,
+};
+
+/// Held for the duration of a run so only one run fixes a workspace at a time
+///
+/// The lock is released when this is dropped, or when the process exits.
+#[derive(Debug)]
+pub struct WorkspaceLock {
+    _file: File,
+}
+
+/// Lock the workspace, waiting up to `wait` for another run to finish
+///
+/// The lock is kept in `target_dir` when given, e.g. from `--target-dir`, or else in the
+/// workspace's target directory.
+///
+/// Returns `None` if the workspace couldn't be determined or the filesystem doesn't support
+/// locking, in which case the run goes ahead unlocked.
+pub fn lock_workspace(
+    target_dir: Option<&Path>,
+    wait: Option<Duration>,
+) -> CargoResult<Option<WorkspaceLock>> {
+    let Some(workspace) = get_workspace() else {
+        return Ok(None);
+    };
+    let target_dir = target_dir.unwrap_or(&workspace.target_directory);
+    paths::create_dir_all(target_dir)?;
+    let path = target_dir.join(".fixit-lock");
+    let file = match OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+    {
+        Ok(file) => file,
+        Err(e) => {
+            warn!("failed to open `{}`: {}", path.display(), e);
+            return Ok(None);
+        }
+    };
+
+    let deadline = wait.map(|wait| Instant::now() + wait);
+    let mut blocked = false;
+    loop {
+        match file.try_lock() {
+            Ok(()) => return Ok(Some(WorkspaceLock { _file: file })),
+            Err(TryLockError::WouldBlock) => {}
+            Err(TryLockError::Error(e)) => {
+                warn!("failed to lock `{}`: {}", path.display(), e);
+                return Ok(None);
+            }
+        }
+
+        let Some(deadline) = deadline else {
+            anyhow::bail!(
+                "another `cargo fixit` is running in this workspace; \
+                use `--wait-for-lock` to wait for it to finish"
+            );
+        };
+        if Instant::now() >= deadline {
+            anyhow::bail!("timed out waiting for another `cargo fixit` in this workspace");
+        }
+        if !blocked {
+            shell::status(
+                "Blocking",
+                "waiting for another `cargo fixit` in this workspace",
+            )?;
+            blocked = true;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
@@ -1,4 +1,7 @@
 pub mod cli;
+pub mod diff;
+pub mod lock;
 pub mod messages;
 pub mod package;
+pub mod run_id;
 pub mod vcs;
@@ -11,3 +11,9 @@ pub fn format_package_id(package_id: &str) -> CargoResult<String> {
 
     Ok(format!("{} v{}", spec.name(), version))
 }
+
+pub fn package_name(package_id: &str) -> CargoResult<String> {
+    let spec = PackageIdSpec::parse(package_id)?;
+
+    Ok(spec.name().to_owned())
+}
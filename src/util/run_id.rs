@@ -0,0 +1,17 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Generate an ID for correlating the outputs of a run, e.g. `1760607000-4f3a9c1e`
+pub fn new_run_id() -> String {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    // `RandomState` is seeded randomly per process, which is all the randomness needed here
+    let suffix = RandomState::new().build_hasher().finish() as u32;
+
+    format!("{timestamp}-{suffix:08x}")
+}
@@ -1,3 +1,5 @@
+//! Guard against commands clobbering uncommitted work
+
 use std::env;
 
 use anyhow::bail;
@@ -21,6 +23,7 @@ pub struct VcsOpts {
 }
 
 impl VcsOpts {
+    /// Error if the working directory is not in a clean VCS, unless allowed by the flags
     pub fn valid_vcs(&self) -> CargoResult<()> {
         if self.allow_no_vcs {
             return Ok(());
@@ -287,22 +287,16 @@ fn prepare_for_2018() {
 
     p.cargo_("fix --edition --allow-no-vcs")
         .with_stderr_data(str![[r#"
-[ERROR] unexpected argument '--edition' found
-
-  tip: a similar argument exists: '--version'
-
-Usage: cargo fixit --version
-
-For more information, try '--help'.
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (2 fixes)
 
 "#]])
         .with_stdout_data("")
-        .with_status(2)
         .run();
 
     println!("{}", p.read_file("src/lib.rs"));
-    assert!(!p.read_file("src/lib.rs").contains("use crate::foo::FOO;"));
-    assert!(!p
+    assert!(p.read_file("src/lib.rs").contains("use crate::foo::FOO;"));
+    assert!(p
         .read_file("src/lib.rs")
         .contains("let x = crate::foo::FOO;"));
 }
@@ -345,20 +339,14 @@ fn fix_tests_with_edition() {
 
     p.cargo_("fix --edition --allow-no-vcs")
         .with_stderr_data(str![[r#"
-[ERROR] unexpected argument '--edition' found
-
-  tip: a similar argument exists: '--version'
-
-Usage: cargo fixit --version
-
-For more information, try '--help'.
+[CHECKING] foo v0.1.0
+[FIXED] src/lib.rs (1 fix)
 
 "#]])
         .with_stdout_data("")
-        .with_status(2)
         .run();
     // Check that the test is fixed.
-    assert!(!p.read_file("src/lib.rs").contains(r#"0..=100 => true,"#));
+    assert!(p.read_file("src/lib.rs").contains(r#"0..=100 => true,"#));
 }
 
 #[cargo_test]
@@ -396,17 +384,12 @@ fn fix_tests_with_edition_idioms() {
 
     p.cargo_("fix --edition-idioms --allow-no-vcs")
         .with_stderr_data(str![[r#"
-[ERROR] unexpected argument '--edition-idioms' found
-
-Usage: cargo fixit [OPTIONS]
-
-For more information, try '--help'.
+[CHECKING] foo v0.1.0
 
 "#]])
         .with_stdout_data("")
-        .with_status(2)
         .run();
-    // Check that the test is fixed.
+    // Tests aren't checked without `--all-targets`.
     assert!(!p.read_file("src/lib.rs").contains("Box<dyn Any>"));
 }
 
@@ -431,21 +414,15 @@ fn local_paths() {
 
     p.cargo_("fix --edition --allow-no-vcs")
         .with_stderr_data(str![[r#"
-[ERROR] unexpected argument '--edition' found
-
-  tip: a similar argument exists: '--version'
-
-Usage: cargo fixit --version
-
-For more information, try '--help'.
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
 
 "#]])
         .with_stdout_data("")
-        .with_status(2)
         .run();
 
     println!("{}", p.read_file("src/lib.rs"));
-    assert!(!p.read_file("src/lib.rs").contains("use crate::test::foo;"));
+    assert!(p.read_file("src/lib.rs").contains("use crate::test::foo;"));
 }
 
 #[cargo_test]
@@ -519,17 +496,11 @@ fn specify_rustflags() {
     p.cargo_("fix --edition --allow-no-vcs")
         .env("RUSTFLAGS", "-C linker=cc")
         .with_stderr_data(str![[r#"
-[ERROR] unexpected argument '--edition' found
-
-  tip: a similar argument exists: '--version'
-
-Usage: cargo fixit --version
-
-For more information, try '--help'.
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
 
 "#]])
         .with_stdout_data("")
-        .with_status(2)
         .run();
 }
 
@@ -1203,21 +1174,15 @@ fn fix_overlapping() {
 
     p.cargo_("fix --allow-no-vcs --edition --lib")
         .with_stderr_data(str![[r#"
-[ERROR] unexpected argument '--edition' found
-
-  tip: a similar argument exists: '--version'
-
-Usage: cargo fixit --allow-no-vcs --version
-
-For more information, try '--help'.
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (2 fixes)
 
 "#]])
-        .with_status(2)
         .run();
 
     let contents = p.read_file("src/lib.rs");
     println!("{}", contents);
-    assert!(!contents.contains("crate::foo::<crate::A>()"));
+    assert!(contents.contains("crate::foo::<crate::A>()"));
 }
 
 #[cargo_test]
@@ -1245,17 +1210,13 @@ fn fix_idioms() {
 
     p.cargo_("fix --edition-idioms --allow-no-vcs")
         .with_stderr_data(str![[r#"
-[ERROR] unexpected argument '--edition-idioms' found
-
-Usage: cargo fixit [OPTIONS]
-
-For more information, try '--help'.
+[CHECKING] foo v0.1.0
+[FIXED] src/lib.rs (1 fix)
 
 "#]])
-        .with_status(2)
         .run();
 
-    assert!(!p.read_file("src/lib.rs").contains("Box<dyn Any>"));
+    assert!(p.read_file("src/lib.rs").contains("Box<dyn Any>"));
 }
 
 #[cargo_test]
@@ -1263,13 +1224,8 @@ fn idioms_2015_ok() {
     let p = project().file("src/lib.rs", "").build();
 
     p.cargo_("fix --edition-idioms --allow-no-vcs")
-        .with_status(2)
         .with_stderr_data(str![[r#"
-[ERROR] unexpected argument '--edition-idioms' found
-
-Usage: cargo fixit [OPTIONS]
-
-For more information, try '--help'.
+[CHECKING] foo v0.0.1
 
 "#]])
         .run();
@@ -1476,16 +1432,10 @@ fn does_not_crash_with_rustc_wrapper() {
     p.build_dir().rm_rf();
     p.cargo_("fix --allow-no-vcs --verbose")
         .env("RUSTC_WORKSPACE_WRAPPER", echo_wrapper())
-        .with_status(2)
         .with_stderr_data(str![[r#"
-[ERROR] unexpected argument '--verbose' found
-
-  tip: a similar argument exists: '--version'
-
-Usage: cargo fixit --allow-no-vcs --version
-
-For more information, try '--help'.
-
+...
+WRAPPER CALLED: [..]rustc --crate-name foo [..]
+...
 "#]])
         .run();
 }
@@ -1508,16 +1458,10 @@ fn uses_workspace_wrapper_and_primary_wrapper_override() {
     p.cargo_("fix --allow-no-vcs --verbose")
         .env("RUSTC_WORKSPACE_WRAPPER", echo_wrapper())
         .with_stderr_data(str![[r#"
-[ERROR] unexpected argument '--verbose' found
-
-  tip: a similar argument exists: '--version'
-
-Usage: cargo fixit --allow-no-vcs --version
-
-For more information, try '--help'.
-
+...
+WRAPPER CALLED: [..]rustc --crate-name foo [..]
+...
 "#]])
-        .with_status(2)
         .run();
 }
 
@@ -1560,16 +1504,10 @@ fn only_warn_for_relevant_crates() {
 
     p.cargo_("fix --allow-no-vcs --edition")
         .with_stderr_data(str![[r#"
-[ERROR] unexpected argument '--edition' found
-
-  tip: a similar argument exists: '--version'
-
-Usage: cargo fixit --allow-no-vcs --version
-
-For more information, try '--help'.
+[CHECKING] a v0.1.0
+[FIXED] a/src/lib.rs (1 fix)
 
 "#]])
-        .with_status(2)
         .run();
 }
 
@@ -1674,20 +1612,19 @@ fn fix_with_common() {
         .build();
 
     p.cargo_("fix --edition --allow-no-vcs")
-        .with_status(2)
-        .with_stderr_data(str![[r#"
-[ERROR] unexpected argument '--edition' found
-
-  tip: a similar argument exists: '--version'
-
-Usage: cargo fixit --version
-
-For more information, try '--help'.
+        .with_stderr_data(
+            str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] tests/t1.rs (1 fix)
+[FIXED] tests/common/mod.rs (1 fix)
+[FIXED] tests/t2.rs (1 fix)
 
-"#]])
+"#]]
+            .unordered(),
+        )
         .run();
 
-    assert_e2e().eq(p.read_file("tests/common/mod.rs"), str!["pub fn try() {}"]);
+    assert_e2e().eq(p.read_file("tests/common/mod.rs"), str!["pub fn r#try() {}"]);
 }
 
 #[cargo_test]
@@ -1791,20 +1728,11 @@ fn edition_v2_resolver_report() {
         .build();
 
     p.cargo_("fix --edition --allow-no-vcs")
-        .with_stderr_data(
-            str![[r#"
-
-
-
-[ERROR] unexpected argument '--edition' found
-  tip: a similar argument exists: '--version'
-Usage: cargo fixit --version
-For more information, try '--help'.
+        .with_stderr_data(str![[r#"
+...
+[CHECKING] foo v0.1.0
 
-"#]]
-            .unordered(),
-        )
-        .with_status(2)
+"#]])
         .run();
 }
 
@@ -1864,18 +1792,12 @@ fn fix_edition_2021() {
         .build();
     p.cargo_("fix --edition --allow-no-vcs")
         .with_stderr_data(str![[r#"
-[ERROR] unexpected argument '--edition' found
-
-  tip: a similar argument exists: '--version'
-
-Usage: cargo fixit --version
-
-For more information, try '--help'.
+[CHECKING] foo v0.1.0
+[FIXED] src/lib.rs (1 fix)
 
 "#]])
-        .with_status(2)
         .run();
-    assert!(!p.read_file("src/lib.rs").contains(r#"0..=100 => true,"#));
+    assert!(p.read_file("src/lib.rs").contains(r#"0..=100 => true,"#));
 }
 
 #[cargo_test]
@@ -2119,15 +2041,11 @@ fn non_edition_lint_migration() {
     // Check that it complains about an unused import.
     p.cargo_("check --lib").run();
     p.cargo_("fix --edition --allow-no-vcs")
-        .with_status(2)
         .with_stderr_data(str![[r#"
-[ERROR] unexpected argument '--edition' found
-
-  tip: a similar argument exists: '--version'
-
-Usage: cargo fixit --version
-
-For more information, try '--help'.
+[CHECKING] foo v0.1.0
+[FIXED] src/lib.rs (1 fix)
+[WARNING] unused import: `std::str::from_utf8`
+...
 
 "#]])
         .run();
@@ -2135,7 +2053,7 @@ For more information, try '--help'.
     // Check it does not remove the "unused" import.
     assert!(contents.contains("use std::str::from_utf8;"));
     // Check that it made the edition migration.
-    assert!(!contents.contains("from_utf8(crate::foo::FOO)"));
+    assert!(contents.contains("from_utf8(crate::foo::FOO)"));
 }
 
 #[cargo_test]
@@ -2730,16 +2648,9 @@ edition = "2021"
 
     p.cargo_("fix --edition --allow-no-vcs")
         .with_stderr_data(str![[r#"
-[ERROR] unexpected argument '--edition' found
-
-  tip: a similar argument exists: '--version'
-
-Usage: cargo fixit --version
-
-For more information, try '--help'.
+[CHECKING] foo v0.0.0
 
 "#]])
-        .with_status(2)
         .run();
     assert_e2e().eq(
         p.read_file("Cargo.toml"),
@@ -2782,16 +2693,9 @@ edition = "2021"
 
     p.cargo_("fix --edition --allow-no-vcs")
         .with_stderr_data(str![[r#"
-[ERROR] unexpected argument '--edition' found
-
-  tip: a similar argument exists: '--version'
-
-Usage: cargo fixit --version
-
-For more information, try '--help'.
+[CHECKING] foo v0.0.0
 
 "#]])
-        .with_status(2)
         .run();
     assert_e2e().eq(
         p.read_file("Cargo.toml"),
@@ -2846,11 +2750,12 @@ fn main() {
     p.cargo_("-Zscript fix --edition --allow-no-vcs --manifest-path foo.rs")
         .masquerade_as_nightly_cargo(&["script"])
         .with_stderr_data(str![[r#"
-[ERROR] unexpected argument '--edition' found
+[ERROR] unexpected argument '--manifest-path' found
 
-  tip: a similar argument exists: '--version'
+  tip: a similar argument exists: '--absolute-paths'
+  tip: to pass '--manifest-path' as a value, use '-- --manifest-path'
 
-Usage: cargo fixit --version
+Usage: cargo fixit --edition --allow-no-vcs --absolute-paths [-- <ARGS>...]
 
 For more information, try '--help'.
 
@@ -2958,16 +2863,10 @@ a = {path = "a", default_features = false}
 
     p.cargo_("fix --edition --allow-no-vcs")
         .with_stderr_data(str![[r#"
-[ERROR] unexpected argument '--edition' found
-
-  tip: a similar argument exists: '--version'
-
-Usage: cargo fixit --version
-
-For more information, try '--help'.
-
+[CHECKING] a v0.0.1
+[CHECKING] foo v0.0.0
+...
 "#]])
-        .with_status(2)
         .run();
     assert_e2e().eq(
         p.read_file("Cargo.toml"),
@@ -3062,16 +2961,9 @@ edition = "2021"
 
     p.cargo_("fix --edition --allow-no-vcs")
         .with_stderr_data(str![[r#"
-[ERROR] unexpected argument '--edition' found
-
-  tip: a similar argument exists: '--version'
-
-Usage: cargo fixit --version
-
-For more information, try '--help'.
+[CHECKING] foo v0.0.0
 
 "#]])
-        .with_status(2)
         .run();
     assert_e2e().eq(
         p.read_file("Cargo.toml"),
@@ -3194,18 +3086,16 @@ dep_df_false = { version = "0.1.0", default-features = false }
     p.cargo_("fix --all --edition --allow-no-vcs")
         .with_stderr_data(
             str![[r#"
-
-
-For more information, try '--help'.
-
-[ERROR] unexpected argument '--edition' found
-  tip: a similar argument exists: '--version'
-Usage: cargo fixit --all --version
+[CHECKING] dep_simple v0.1.0
+[CHECKING] dep_df_true v0.1.0
+[CHECKING] dep_df_false v0.1.0
+[CHECKING] pkg_default v0.1.0
+[CHECKING] pkg_df_true v0.1.0
+[CHECKING] pkg_df_false v0.1.0
 
 "#]]
             .unordered(),
         )
-        .with_status(2)
         .run();
 
     assert_e2e().eq(p.read_file("pkg_default/Cargo.toml"), pkg_default);
@@ -3273,28 +3163,20 @@ fn fix_edition_skips_old_editions() {
     p.cargo_("fix -Zfix-edition=start=2024 -v")
         .masquerade_as_nightly_cargo(&["fix-edition"])
         .with_stderr_data(str![[r#"
-[ERROR] unexpected argument '-v' found
-
-Usage: cargo fixit [OPTIONS]
-
-For more information, try '--help'.
+[ERROR] no VCS found for this package and `cargo fix` can potentially perform destructive changes; if you'd like to suppress this error pass `--allow-no-vcs`
 
 "#]])
-        .with_status(2)
+        .with_status(101)
         .run();
 
     // Same with `end`.
     p.cargo_("fix -Zfix-edition=end=2024,future -v")
         .masquerade_as_nightly_cargo(&["fix-edition"])
         .with_stderr_data(str![[r#"
-[ERROR] unexpected argument '-v' found
-
-Usage: cargo fixit [OPTIONS]
-
-For more information, try '--help'.
+[ERROR] no VCS found for this package and `cargo fix` can potentially perform destructive changes; if you'd like to suppress this error pass `--allow-no-vcs`
 
 "#]])
-        .with_status(2)
+        .with_status(101)
         .run();
 
     // Doing an individual package at the correct edition should check it.
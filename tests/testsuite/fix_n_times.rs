@@ -375,6 +375,8 @@ rustc fix shim comment 5
 
 rustc fix shim comment 6
 
+[WARNING] stopped fixing foo v0.0.1 after 4 checks as its fixes kept changing the code
+[NOTE] raise the limit with `--max-retries`
 
 "#]],
         "// fix-count 5",
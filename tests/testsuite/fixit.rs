@@ -1,5 +1,12 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
 use cargo_test_macro::cargo_test;
-use cargo_test_support::{basic_manifest, compare::assert_ui, project};
+use cargo_test_support::prelude::*;
+use cargo_test_support::{basic_manifest, compare::assert_ui, execs, process, project, Project};
 use snapbox::str;
 
 use crate::fix::FixitProject;
@@ -219,3 +226,2362 @@ fn print_errors_after_fixed() {
 "#]])
         .run();
 }
+
+#[cargo_test]
+fn bin_progress() {
+    let p = project()
+        .file("src/bin/a.rs", "fn main() { let mut x = 3; let _ = x; }")
+        .file("src/bin/b.rs", "fn main() { let mut x = 3; let _ = x; }")
+        .build();
+
+    // Which binary cargo reports first varies, but the count always goes up in order
+    p.cargo_("fixit --allow-no-vcs")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[FIXING] bin "[..]" 1/2
+[CHECKING] foo v0.0.1
+[FIXED] src/bin/[..].rs (1 fix)
+[FIXING] bin "[..]" 2/2
+[FIXED] src/bin/[..].rs (1 fix)
+
+"#]])
+        .run();
+    for bin in ["src/bin/a.rs", "src/bin/b.rs"] {
+        assert_eq!(p.read_file(bin), "fn main() { let x = 3; let _ = x; }");
+    }
+}
+
+#[cargo_test]
+fn lockfile_path() {
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --lockfile-path locks/Cargo.lock")
+        .masquerade_as_nightly_cargo(&["lockfile-path"])
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+    assert!(p.root().join("locks/Cargo.lock").is_file());
+    assert!(!p.root().join("Cargo.lock").exists());
+}
+
+#[cargo_test]
+fn multiple_bins() {
+    let p = project()
+        .file("src/bin/a.rs", "fn main() { let mut x = 3; let _ = x; }")
+        .file("src/bin/b.rs", "fn main() { let mut x = 3; let _ = x; }")
+        .file("src/bin/c.rs", "fn main() { let mut x = 3; let _ = x; }")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --bin a --bin c")
+        .with_status(0)
+        .with_stderr_data(
+            str![[r#"
+[FIXING] bin "[..]" 1/2
+[CHECKING] foo v0.0.1
+[FIXED] src/bin/a.rs (1 fix)
+[FIXING] bin "[..]" 2/2
+[FIXED] src/bin/c.rs (1 fix)
+
+"#]]
+            .unordered(),
+        )
+        .run();
+    assert_ui().eq(
+        p.read_file("src/bin/b.rs"),
+        str!["fn main() { let mut x = 3; let _ = x; }"],
+    );
+}
+
+#[cargo_test]
+fn workspace_relative_paths() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+            pub fn a() {
+                let mut b = 10;
+                let _ = b;
+            }
+            "#,
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs")
+        .cwd(p.root().join("src"))
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn absolute_paths() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+            pub fn a() {
+                let mut b = 10;
+                let _ = b;
+            }
+            "#,
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --absolute-paths")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] [ROOT]/foo/src/lib.rs (1 fix)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn max_file_size() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+            pub fn a() {
+                let mut b = 10;
+                let _ = b;
+            }
+            "#,
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --max-file-size 10")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[WARNING] skipping suggestions for `src/lib.rs` as it is larger than 10 bytes
+[CHECKING] foo v0.0.1
+[WARNING] variable does not need to be mutable
+...
+"#]])
+        .run();
+    assert_ui().eq(
+        p.read_file("src/lib.rs"),
+        str![[r#"
+
+            pub fn a() {
+                let mut b = 10;
+                let _ = b;
+            }
+            
+"#]],
+    );
+}
+
+#[cargo_test]
+fn skip_file_marker() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            "// fixit:skip-file\npub fn f() { let mut x = 3; let _ = x; }\n",
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[NOTE] skipping suggestions for `src/lib.rs` as it has `// fixit:skip-file`
+[CHECKING] foo v0.0.1
+[WARNING] variable does not need to be mutable
+...
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("let mut x = 3;"));
+}
+
+#[cargo_test]
+fn missing_file() {
+    // A rustc wrapper that removes `src/gone.rs` once the library is checked, as if it was moved
+    // before the fixes were applied
+    let wrapper = project()
+        .at("wrapper")
+        .file("Cargo.toml", &basic_manifest("wrapper", "0.1.0"))
+        .file(
+            "src/main.rs",
+            r#"
+use std::{env, fs, process::{self, Command}};
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let status = Command::new(&args[0]).args(&args[1..]).status().unwrap();
+    if args.iter().any(|arg| arg == "src/lib.rs") {
+        let _ = fs::remove_file("src/gone.rs");
+    }
+    process::exit(status.code().unwrap_or(2));
+}
+"#,
+        )
+        .build();
+    wrapper.cargo_("build").run();
+
+    let p = project()
+        .file("src/lib.rs", "mod gone;\n")
+        .file("src/gone.rs", "pub fn f() { let mut x = 3; let _ = x; }\n")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs")
+        .env("RUSTC_WORKSPACE_WRAPPER", wrapper.bin("wrapper"))
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[WARNING] skipping suggestions for `src/gone.rs` as it no longer exists
+[NOTE] the build output may be stale; try `cargo clean -p foo`
+[CHECKING] foo v0.0.1
+...
+error[E0583]: file not found for module `gone`
+...
+"#]])
+        .run();
+}
+
+/// A rustc wrapper that replaces the `é` in `src/lib.rs` with invalid UTF-8 once the library is
+/// checked, keeping the suggestions' byte ranges
+fn non_utf8_wrapper() -> Project {
+    let wrapper = project()
+        .at("wrapper")
+        .file("Cargo.toml", &basic_manifest("wrapper", "0.1.0"))
+        .file(
+            "src/main.rs",
+            r#"
+use std::{env, fs, process::{self, Command}};
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let status = Command::new(&args[0]).args(&args[1..]).status().unwrap();
+    if args.iter().any(|arg| arg == "src/lib.rs") {
+        let source = fs::read_to_string("src/lib.rs").unwrap();
+        let (before, after) = source.split_once('é').unwrap();
+        let source = [before.as_bytes(), b"\xff\xff", after.as_bytes()].concat();
+        fs::write("src/lib.rs", source).unwrap();
+    }
+    process::exit(status.code().unwrap_or(2));
+}
+"#,
+        )
+        .build();
+    wrapper.cargo_("build").run();
+    wrapper
+}
+
+#[cargo_test]
+fn non_utf8() {
+    let wrapper = non_utf8_wrapper();
+
+    let p = project()
+        .file("src/lib.rs", "// é\npub fn f() { let mut x = 3; let _ = x; }\n")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --quick")
+        .env("RUSTC_WORKSPACE_WRAPPER", wrapper.bin("wrapper"))
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[WARNING] skipping suggestions for `src/lib.rs` as it is not valid UTF-8
+[WARNING] variable does not need to be mutable
+...
+"#]])
+        .run();
+    assert_eq!(
+        fs::read(p.root().join("src/lib.rs")).unwrap(),
+        b"// \xff\xff\npub fn f() { let mut x = 3; let _ = x; }\n"
+    );
+
+    // Suggestions before the first invalid byte are still applied
+    p.change_file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }\n// é\n");
+    p.cargo_("fixit --allow-no-vcs --quick")
+        .env("RUSTC_WORKSPACE_WRAPPER", wrapper.bin("wrapper"))
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+    assert_eq!(
+        fs::read(p.root().join("src/lib.rs")).unwrap(),
+        b"pub fn f() { let x = 3; let _ = x; }\n// \xff\xff\n"
+    );
+}
+
+#[cargo_test]
+fn generated_code() {
+    let p = project()
+        .file(
+            "build.rs",
+            r#"
+fn main() {
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    let code = "pub fn generated() { let mut x = 3; let _ = x; }";
+    std::fs::write(format!("{out_dir}/generated.rs"), code).unwrap();
+}
+"#,
+        )
+        .file(
+            "src/lib.rs",
+            r#"
+include!(concat!(env!("OUT_DIR"), "/generated.rs"));
+
+pub fn f() { let mut x = 3; let _ = x; }
+"#,
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+[WARNING] variable does not need to be mutable
+ --> [ROOT]/foo/target/debug/build/foo-[HASH]/out/generated.rs:1:26
+...
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("pub fn f() { let x = 3;"));
+}
+
+#[cargo_test]
+#[cfg(any(target_os = "macos", windows))]
+fn case_insensitive_paths() {
+    let p = project()
+        .file("src/lib.rs", "mod foo;\n#[path = \"FOO.rs\"]\nmod bar;\n")
+        .file("src/foo.rs", "use std::io;\n")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/foo.rs ([..])
+...
+"#]])
+        .run();
+    assert!(!p.read_file("src/foo.rs").contains("use std::io;"));
+}
+
+#[cargo_test]
+fn lockfile_changes() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.lock",
+            r#"
+            version = 4
+
+            [[package]]
+            name = "foo"
+            version = "0.0.2"
+            "#,
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[WARNING] `Cargo.lock` was modified by cargo while fixing
+[NOTE] review the changes before committing, or use `--ignore-lockfile-changes` to silence this warning
+
+"#]])
+        .run();
+
+    p.change_file(
+        "Cargo.lock",
+        r#"
+        version = 4
+
+        [[package]]
+        name = "foo"
+        version = "0.0.2"
+        "#,
+    );
+    p.cargo_("fixit --allow-no-vcs --ignore-lockfile-changes")
+        .with_status(0)
+        .with_stderr_data("")
+        .run();
+}
+
+#[cargo_test]
+fn env() {
+    let p = project()
+        .file(
+            "build.rs",
+            r#"
+            fn main() {
+                assert_eq!(std::env::var("FIXIT_TEST_ENV").unwrap(), "1");
+            }
+            "#,
+        )
+        .file(
+            "src/lib.rs",
+            r#"
+            pub fn a() {
+                let mut b = 10;
+                let _ = b;
+            }
+            "#,
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --env FIXIT_TEST_ENV=1")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn hermetic_env() {
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }\n")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --hermetic-env")
+        .env("RUSTFLAGS", "-Aunused_mut")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[NOTE] not passing [..]`RUSTFLAGS`[..] to cargo as `--hermetic-env` was passed
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("let x = 3;"));
+}
+
+#[cargo_test]
+fn skip_ledger() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+            pub fn a() {
+                let mut b = 10;
+                let _ = b;
+            }
+            "#,
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --max-file-size 10 --skip-ledger skips.json")
+        .with_status(0)
+        .run();
+    assert_ui().eq(
+        p.read_file("skips.json"),
+        str![[r#"
+{
+  "run_id": "[..]",
+  "skipped": [
+    {
+      "column_end": [..],
+      "column_start": [..],
+      "file": "src/lib.rs",
+      "line_end": 3,
+      "line_start": 3,
+      "lint": "unused_mut",
+      "reason": "too-large"
+    }
+  ]
+}
+
+"#]],
+    );
+}
+
+#[cargo_test]
+fn skip_ledger_unapplied_suggestions() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            "use std::io;\npub fn f() { let mut a = 1; let mut b = 2; let _ = (a, b); }\n",
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --interactive --ignore unused_imports --skip-ledger skips.json")
+        .with_stdin("n\ny\n")
+        .with_status(0)
+        .run();
+    assert_ui().eq(
+        p.read_file("skips.json"),
+        str![[r#"
+{
+  "run_id": "[..]",
+  "skipped": [
+    {
+      "column_end": 12,
+      "column_start": 5,
+      "file": "src/lib.rs",
+      "line_end": 1,
+      "line_start": 1,
+      "lint": "unused_imports",
+      "reason": "ignored"
+    },
+    {
+      "column_end": 23,
+      "column_start": 18,
+      "file": "src/lib.rs",
+      "line_end": 2,
+      "line_start": 2,
+      "lint": "unused_mut",
+      "reason": "declined"
+    }
+  ]
+}
+
+"#]],
+    );
+}
+
+#[cargo_test]
+fn without_cargo_env() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+            pub fn a() {
+                let mut b = 10;
+                let _ = b;
+            }
+            "#,
+        )
+        .build();
+
+    let mut fixit = process(env!("CARGO_BIN_EXE_cargo-fixit"));
+    fixit
+        .cwd(p.root())
+        .args(&["fixit", "--allow-no-vcs"])
+        .env_remove("CARGO");
+    execs()
+        .with_process_builder(fixit)
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn cargo_path() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo_("fixit --allow-no-vcs --cargo-path does-not-exist")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to run `does-not-exist`: [..]
+
+"#]])
+        .run();
+
+    p.cargo_("fixit --allow-no-vcs")
+        .env("FIXIT_CARGO", "does-not-exist")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] failed to run `does-not-exist`: [..]
+
+"#]])
+        .run();
+}
+
+/// A `rustc` that claims to be old, and otherwise runs the one in `REAL_RUSTC`
+///
+/// It also works as a `RUSTC_WRAPPER`, which is given the `rustc` to run first.
+fn old_rustc() -> Project {
+    let fake = project()
+        .at("fake-rustc")
+        .file("Cargo.toml", &basic_manifest("rustc", "0.1.0"))
+        .file(
+            "src/main.rs",
+            r#"
+use std::{env, path::Path, process::{self, Command}};
+
+fn main() {
+    let mut args = env::args().skip(1).collect::<Vec<_>>();
+    if args.first().is_some_and(|arg| Path::new(arg).file_stem().is_some_and(|s| s == "rustc")) {
+        args.remove(0);
+    }
+    if args == ["--version"] {
+        println!("rustc 1.0.0 (a59807500 2015-05-15)");
+        return;
+    }
+    let rustc = env::var("REAL_RUSTC").unwrap();
+    let status = Command::new(rustc).args(&args).status().unwrap();
+    process::exit(status.code().unwrap_or(2));
+}
+"#,
+        )
+        .build();
+    fake.cargo_("build").run();
+    fake
+}
+
+fn real_rustc() -> PathBuf {
+    let sysroot = Command::new("rustc")
+        .arg("--print=sysroot")
+        .output()
+        .unwrap()
+        .stdout;
+    Path::new(String::from_utf8(sysroot).unwrap().trim())
+        .join("bin")
+        .join(format!("rustc{}", env::consts::EXE_SUFFIX))
+}
+
+#[cargo_test]
+fn old_toolchain() {
+    let fake = old_rustc();
+    let real_rustc = real_rustc();
+    let path = env::join_paths(
+        [
+            fake.root().join("target/debug"),
+            Path::new(env!("CARGO_BIN_EXE_cargo-fixit"))
+                .parent()
+                .unwrap()
+                .to_owned(),
+        ]
+        .into_iter()
+        .chain(env::split_paths(env!("PATH"))),
+    )
+    .unwrap();
+
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }\n")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs")
+        .env("PATH", &path)
+        .env("REAL_RUSTC", &real_rustc)
+        .env_remove("RUSTC")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[NOTE] try using `--allow-old-toolchain` to fix anyway
+[ERROR] rustc 1.0.0 is older than [..], the oldest version cargo-fixit is tested with; its suggestions may corrupt your code
+
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("let mut x = 3;"));
+
+    p.cargo_("fixit --allow-no-vcs --allow-old-toolchain")
+        .env("PATH", &path)
+        .env("REAL_RUSTC", &real_rustc)
+        .env_remove("RUSTC")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[WARNING] rustc 1.0.0 is older than [..], the oldest version cargo-fixit is tested with; its suggestions may corrupt your code
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn old_toolchain_from_cargo() {
+    let fake = old_rustc();
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }\n")
+        .build();
+
+    // The version is taken from the rustc cargo would run
+    p.cargo_("fixit --allow-no-vcs")
+        .env("RUSTC", fake.bin("rustc"))
+        .env("REAL_RUSTC", real_rustc())
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[NOTE] try using `--allow-old-toolchain` to fix anyway
+[ERROR] rustc 1.0.0 is older than [..], the oldest version cargo-fixit is tested with; its suggestions may corrupt your code
+
+"#]])
+        .run();
+
+    p.cargo_("fixit --allow-no-vcs")
+        .arg("--config")
+        .arg(format!("build.rustc = '{}'", fake.bin("rustc").display()))
+        .env("REAL_RUSTC", real_rustc())
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[NOTE] try using `--allow-old-toolchain` to fix anyway
+[ERROR] rustc 1.0.0 is older than [..], the oldest version cargo-fixit is tested with; its suggestions may corrupt your code
+
+"#]])
+        .run();
+
+    p.cargo_("fixit --allow-no-vcs")
+        .env("RUSTC_WRAPPER", fake.bin("rustc"))
+        .env("REAL_RUSTC", real_rustc())
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[NOTE] try using `--allow-old-toolchain` to fix anyway
+[ERROR] rustc 1.0.0 is older than [..], the oldest version cargo-fixit is tested with; its suggestions may corrupt your code
+
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("let mut x = 3;"));
+}
+
+#[cargo_test]
+fn apply_threads() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+            pub mod a;
+            pub mod b;
+
+            pub fn f() {
+                let mut x = 10;
+                let _ = x;
+            }
+            "#,
+        )
+        .file("src/a.rs", "pub fn f() { let mut x = 3; let _ = x; }")
+        .file("src/b.rs", "pub fn f() { let mut x = 3; let _ = x; }")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --apply-threads 3")
+        .with_status(0)
+        .with_stderr_data(
+            str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+[FIXED] src/a.rs (1 fix)
+[FIXED] src/b.rs (1 fix)
+
+"#]]
+            .unordered(),
+        )
+        .run();
+    assert_ui().eq(
+        p.read_file("src/a.rs"),
+        str!["pub fn f() { let x = 3; let _ = x; }"],
+    );
+}
+
+#[cargo_test]
+fn quick() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+            pub fn f() {
+                let mut x = 10;
+                let _ = x;
+            }
+            "#,
+        )
+        .file("tests/t.rs", "#[test] fn t() { let mut x = 3; let _ = x; }")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --all-targets --quick")
+        .with_status(0)
+        .with_stderr_data(
+            str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+[FIXED] tests/t.rs (1 fix)
+
+"#]]
+            .unordered(),
+        )
+        .run();
+    assert_ui().eq(
+        p.read_file("tests/t.rs"),
+        str!["#[test] fn t() { let x = 3; let _ = x; }"],
+    );
+}
+
+#[cargo_test]
+fn dry_run() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            "use std::io;\npub fn f() { let mut x = 3; let _ = x; }\n",
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --dry-run")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+     Fixable src/lib.rs (2 fixes: unused_imports, unused_mut)
+
+"#]])
+        .run();
+    assert_eq!(
+        p.read_file("src/lib.rs"),
+        "use std::io;\npub fn f() { let mut x = 3; let _ = x; }\n"
+    );
+}
+
+#[cargo_test]
+fn check() {
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }\n")
+        .build();
+
+    p.cargo_("fixit --check")
+        .with_status(1)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+     Fixable src/lib.rs (1 fix: unused_mut)
+[ERROR] 1 file can be fixed; run `cargo fixit` to apply the fixes
+
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("let mut x = 3;"));
+
+    p.change_file("src/lib.rs", "pub fn f() { let x = 3; let _ = x; }\n");
+    p.cargo_("fixit --check")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn diff() {
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }\n")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --diff --dry-run")
+        .with_stdout_data(str![[r#"
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,1 +1,1 @@
+-pub fn f() { let mut x = 3; let _ = x; }
++pub fn f() { let x = 3; let _ = x; }
+
+"#]])
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+     Fixable src/lib.rs (1 fix: unused_mut)
+
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("let mut x = 3;"));
+}
+
+#[cargo_test]
+fn diff_and_write() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            "pub mod a;\npub fn f() { let mut x = 3; let _ = x; }\n",
+        )
+        .file("src/a.rs", "pub fn g() -> u32 { let y = (1); y }\n")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --diff --apply-threads 2")
+        .with_stdout_data(str![[r#"
+--- a/src/a.rs
++++ b/src/a.rs
+@@ -1,1 +1,1 @@
+-pub fn g() -> u32 { let y = (1); y }
++pub fn g() -> u32 { let y = 1 ; y }
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,2 +1,2 @@
+ pub mod a;
+-pub fn f() { let mut x = 3; let _ = x; }
++pub fn f() { let x = 3; let _ = x; }
+
+"#]])
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/a.rs (1 fix)
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+    assert!(p.read_file("src/a.rs").contains("let y = 1 ;"));
+    assert!(p.read_file("src/lib.rs").contains("let x = 3;"));
+}
+
+#[cargo_test]
+fn interactive() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+            pub fn f() {
+                let mut a = 1;
+                let mut b = 2;
+                let _ = (a, b);
+            }
+            "#,
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --interactive")
+        .with_stdin("n\ny\n")
+        .with_stderr_data(str![[r#"
+...
+[FIXED] src/lib.rs (1 fix)
+...
+"#]])
+        .run();
+    let source = p.read_file("src/lib.rs");
+    assert!(source.contains("let mut a = 1;"));
+    assert!(source.contains("let b = 2;"));
+}
+
+#[cargo_test]
+fn interactive_non_utf8() {
+    let wrapper = non_utf8_wrapper();
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }\n// é\n")
+        .build();
+
+    // Suggestions before the first invalid byte would be applied, so they are reviewed too
+    p.cargo_("fixit --allow-no-vcs --quick --interactive")
+        .env("RUSTC_WORKSPACE_WRAPPER", wrapper.bin("wrapper"))
+        .with_stdin("n\n")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[WARNING] variable does not need to be mutable
+...
+-pub fn f() { let mut x = 3; let _ = x; }
++pub fn f() { let x = 3; let _ = x; }
+...
+"#]])
+        .run();
+    assert_eq!(
+        fs::read(p.root().join("src/lib.rs")).unwrap(),
+        b"pub fn f() { let mut x = 3; let _ = x; }\n// \xff\xff\n"
+    );
+}
+
+#[cargo_test]
+fn edition_per_package() {
+    let source = r#"
+pub fn f(x: u8) -> bool {
+    match x {
+        0...100 => true,
+        _ => false,
+    }
+}
+"#;
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                members = ["a", "b"]
+                resolver = "2"
+            "#,
+        )
+        .file(
+            "a/Cargo.toml",
+            r#"
+                [package]
+                name = "a"
+                version = "0.1.0"
+                edition = "2015"
+            "#,
+        )
+        .file("a/src/lib.rs", source)
+        .file(
+            "b/Cargo.toml",
+            r#"
+                [package]
+                name = "b"
+                version = "0.1.0"
+                edition = "2018"
+            "#,
+        )
+        .file("b/src/lib.rs", source)
+        .build();
+
+    // `a` is only migrated to 2018, so the 2021 lints are left alone
+    p.cargo_("fixit --allow-no-vcs --edition")
+        .with_status(0)
+        .with_stderr_data(
+            str![[r#"
+[CHECKING] a v0.1.0
+[CHECKING] b v0.1.0
+[FIXED] b/src/lib.rs (1 fix)
+[WARNING] `...` range patterns are deprecated
+...
+"#]]
+            .unordered(),
+        )
+        .run();
+    assert!(p.read_file("a/src/lib.rs").contains("0...100"));
+    assert!(p.read_file("b/src/lib.rs").contains("0..=100"));
+}
+
+#[cargo_test]
+fn edition_selected_targets() {
+    let source = r#"
+pub fn f(x: u8) -> bool {
+    match x {
+        0...100 => true,
+        _ => false,
+    }
+}
+"#;
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "foo"
+                version = "0.1.0"
+                edition = "2018"
+            "#,
+        )
+        .file("src/lib.rs", source)
+        .file("tests/t.rs", source)
+        .build();
+
+    // Selecting a target replaces the default of `--all-targets`
+    p.cargo_("fixit --allow-no-vcs --edition --lib")
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.1.0
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("0..=100"));
+    assert!(p.read_file("tests/t.rs").contains("0...100"));
+
+    p.cargo_("fixit --allow-no-vcs --edition")
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.1.0
+[FIXED] tests/t.rs (1 fix)
+
+"#]])
+        .run();
+    assert!(p.read_file("tests/t.rs").contains("0..=100"));
+}
+
+#[cargo_test]
+fn only_and_ignore() {
+    let source = "use std::io;\npub fn f() { let mut x = 3; let _ = x; }\n";
+    let p = project().file("src/lib.rs", source).build();
+
+    p.cargo_("fixit --allow-no-vcs --only unused-mut")
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+[WARNING] unused import: `std::io`
+...
+"#]])
+        .run();
+    assert_eq!(
+        p.read_file("src/lib.rs"),
+        "use std::io;\npub fn f() { let x = 3; let _ = x; }\n"
+    );
+
+    p.change_file("src/lib.rs", source);
+    p.cargo_("fixit --allow-no-vcs --ignore unused_mut")
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+[WARNING] variable does not need to be mutable
+...
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("let mut x = 3;"));
+    assert!(!p.read_file("src/lib.rs").contains("use std::io;"));
+}
+
+#[cargo_test]
+fn release() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+            #[cfg(not(debug_assertions))]
+            pub fn f() {
+                let mut x = 10;
+                let _ = x;
+            }
+            "#,
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs")
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+
+"#]])
+        .run();
+
+    p.cargo_("fixit --allow-no-vcs --release")
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("let x = 10;"));
+}
+
+#[cargo_test]
+fn offline() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+            pub fn f() {
+                let mut x = 10;
+                let _ = x;
+            }
+            "#,
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --offline")
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn jobs() {
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs -j 1")
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn keep_going() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["a", "b"]
+            "#,
+        )
+        .file("a/Cargo.toml", &basic_manifest("a", "0.1.0"))
+        .file("a/src/lib.rs", "pub fn f() -> u32 { \"\" }")
+        .file("b/Cargo.toml", &basic_manifest("b", "0.1.0"))
+        .file("b/src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --broken-code --keep-going -j 1")
+        .with_stderr_data(str![[r#"
+...
+[FIXED] b/src/lib.rs (1 fix)
+...
+"#]])
+        .run();
+    assert_ui().eq(
+        p.read_file("b/src/lib.rs"),
+        str!["pub fn f() { let x = 3; let _ = x; }"],
+    );
+}
+
+#[cargo_test]
+fn target_dir() {
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --target-dir fixit-target")
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+    assert!(p.root().join("fixit-target").is_dir());
+    assert!(!p.root().join("target").exists());
+}
+
+#[cargo_test]
+fn quiet() {
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --quiet")
+        .with_stderr_data(str![""])
+        .run();
+    assert_ui().eq(
+        p.read_file("src/lib.rs"),
+        str!["pub fn f() { let x = 3; let _ = x; }"],
+    );
+}
+
+#[cargo_test]
+fn workspace_lock() {
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }")
+        .build();
+
+    std::fs::create_dir_all(p.root().join("target")).unwrap();
+    let lock = std::fs::File::create(p.root().join("target/.fixit-lock")).unwrap();
+    lock.lock().unwrap();
+
+    p.cargo_("fixit --allow-no-vcs")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] another `cargo fixit` is running in this workspace; use `--wait-for-lock` to wait for it to finish
+
+"#]])
+        .run();
+
+    p.cargo_("fixit --allow-no-vcs --wait-for-lock 1")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[BLOCKING] waiting for another `cargo fixit` in this workspace
+[ERROR] timed out waiting for another `cargo fixit` in this workspace
+
+"#]])
+        .run();
+
+    drop(lock);
+    p.cargo_("fixit --allow-no-vcs --wait-for-lock 1")
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+}
+
+#[cfg(unix)]
+#[cargo_test]
+fn nice() {
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --nice 10")
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn clippy_args_require_clippy() {
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo_("fixit --allow-no-vcs -- -W clippy::pedantic")
+        .with_status(2)
+        .with_stderr_data(str![[r#"
+[ERROR] the following required arguments were not provided:
+  --clippy
+
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn clippy_not_installed() {
+    // Leave out the directories with a `cargo-clippy`, e.g. rustup's proxies
+    let clippy = format!("cargo-clippy{}", env::consts::EXE_SUFFIX);
+    let path = env::join_paths(
+        [Path::new(env!("CARGO_BIN_EXE_cargo-fixit"))
+            .parent()
+            .unwrap()
+            .to_owned()]
+        .into_iter()
+        .chain(env::split_paths(env!("PATH")).filter(|dir| !dir.join(&clippy).exists())),
+    )
+    .unwrap();
+
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }\n")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --clippy")
+        .env("PATH", &path)
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[NOTE] try installing it with `rustup component add clippy` or using `--auto-install-components`
+[ERROR] `cargo clippy` is not installed for the active toolchain
+
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("let mut x = 3;"));
+}
+
+#[cargo_test]
+fn cargo_retried() {
+    // A cargo that fails to lock a file on its first check, and otherwise runs the real one
+    let fake = project()
+        .at("fake-cargo")
+        .file("Cargo.toml", &basic_manifest("cargo", "0.1.0"))
+        .file(
+            "src/main.rs",
+            r#"
+use std::{env, fs, process::{self, Command}};
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let marker = env::var("RETRY_MARKER").unwrap();
+    if args.first().is_some_and(|arg| arg == "check") && fs::metadata(&marker).is_err() {
+        fs::write(&marker, "").unwrap();
+        eprintln!("error: failed to lock file: Resource temporarily unavailable");
+        process::exit(101);
+    }
+    let cargo = env::var("REAL_CARGO").unwrap();
+    let status = Command::new(cargo).args(&args).status().unwrap();
+    process::exit(status.code().unwrap_or(2));
+}
+"#,
+        )
+        .build();
+    fake.cargo_("build").run();
+
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }\n")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs")
+        .env("FIXIT_CARGO", fake.bin("cargo"))
+        .env("REAL_CARGO", env!("CARGO"))
+        .env("RETRY_MARKER", p.root().join("retried"))
+        .with_stderr_data(str![[r#"
+[WARNING] cargo failed (resource temporarily unavailable), retrying in 500ms (1/2)
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn cargo_out_of_memory() {
+    // A cargo whose compiler fails to allocate
+    let fake = project()
+        .at("fake-cargo")
+        .file("Cargo.toml", &basic_manifest("cargo", "0.1.0"))
+        .file(
+            "src/main.rs",
+            r#"
+use std::process;
+
+fn main() {
+    eprintln!("memory allocation of 4294967296 bytes failed");
+    process::exit(101);
+}
+"#,
+        )
+        .build();
+    fake.cargo_("build").run();
+
+    let p = project().file("src/lib.rs", "").build();
+
+    p.cargo_("fixit --allow-no-vcs")
+        .env("FIXIT_CARGO", fake.bin("cargo"))
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[WARNING] cargo failed as the compiler ran out of memory
+[NOTE] try running fewer jobs in parallel with `--jobs`
+[NOTE] try using `--broken-code` to fix errors
+[ERROR] could not compile
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn errors_fixed_first() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+            fn foo(a: &u32) -> u32 { a + 1 }
+            pub fn bar() {
+                let mut x = 1;
+                foo(x);
+            }
+            "#,
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --broken-code")
+        .env("__CARGO_FIX_YOLO", "1")
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (2 fixes)
+
+"#]])
+        .run();
+    let source = p.read_file("src/lib.rs");
+    assert!(source.contains("let x = 1;"));
+    assert!(source.contains("foo(&x);"));
+
+    // Without re-checking, the warning's fix is left for a later run
+    p.change_file(
+        "src/lib.rs",
+        r#"
+            fn foo(a: &u32) -> u32 { a + 1 }
+            pub fn bar() -> u32 {
+                let x = (1);
+                foo(x)
+            }
+            "#,
+    );
+    p.cargo_("fixit --allow-no-vcs --broken-code --quick")
+        .env("__CARGO_FIX_YOLO", "1")
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+...
+[WARNING] unnecessary parentheses around assigned value
+...
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+    let source = p.read_file("src/lib.rs");
+    assert!(source.contains("let x = (1);"));
+    assert!(source.contains("foo(&x)"));
+}
+
+#[cargo_test]
+fn apply_maybe_incorrect() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+            pub fn bar() -> u32 {
+                let value = 1;
+                valeu
+            }
+            "#,
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --broken-code --yolo")
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+[WARNING] suggestions that may be incorrect were applied
+[NOTE] review the changes before committing them
+
+"#]])
+        .run();
+    assert!(!p.read_file("src/lib.rs").contains("valeu"));
+}
+
+#[cargo_test]
+fn max_retries() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+            fn foo(a: &u32) -> u32 { a + 1 }
+            pub fn bar() {
+                let mut x = 1;
+                foo(x);
+            }
+            "#,
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --broken-code --max-retries 1")
+        .env("__CARGO_FIX_YOLO", "1")
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+[WARNING] variable does not need to be mutable
+...
+[WARNING] stopped fixing foo v0.0.1 after 1 check as its fixes kept changing the code
+[NOTE] raise the limit with `--max-retries`
+
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("let mut x = 1;"));
+}
+
+#[cargo_test]
+fn reported_again() {
+    // The build script undoes the fix, so the same suggestion is reported on every check
+    let p = project()
+        .file(
+            "build.rs",
+            r#"
+fn main() {
+    println!("cargo::rerun-if-changed=src/lib.rs");
+    std::fs::write("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }\n").unwrap();
+}
+"#,
+        )
+        .file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }\n")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --max-retries 2")
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+[WARNING] variable does not need to be mutable
+...
+[WARNING] stopped fixing foo v0.0.1 after 2 checks as its fixes kept changing the code
+[NOTE] raise the limit with `--max-retries`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn ignore_rust_version() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            edition = "2015"
+            rust-version = "1.9876.0"
+            "#,
+        )
+        .file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --ignore-rust-version")
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn doc_mode() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+            #![deny(rustdoc::bare_urls)]
+
+            /// See https://example.com
+            pub fn f() {}
+            "#,
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --mode doc")
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+    assert!(p
+        .read_file("src/lib.rs")
+        .contains("/// See <https://example.com>"));
+}
+
+#[cargo_test]
+fn overridden_cap_lints() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+            pub fn a() {
+                let mut b = 10;
+                let _ = b;
+            }
+            "#,
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs")
+        .env("RUSTFLAGS", "--cap-lints=allow")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[WARNING] ignoring `--cap-lints=allow` from `RUSTFLAGS` as cargo-fixit needs to control lint capping
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn overridden_diagnostic_format() {
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }\n")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs")
+        .env(
+            "RUSTFLAGS",
+            "--error-format=short --json artifacts -Z unstable-options",
+        )
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[WARNING] ignoring `--error-format=short` from `RUSTFLAGS` as cargo-fixit needs to read rustc's JSON diagnostics
+[WARNING] ignoring `--json artifacts` from `RUSTFLAGS` as cargo-fixit needs to read rustc's JSON diagnostics
+[WARNING] ignoring `-Z unstable-options` from `RUSTFLAGS` as it enables unstable diagnostic formats
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn overridden_rustc_bootstrap() {
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }\n")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --json-output -")
+        .env("RUSTC_BOOTSTRAP", "1")
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+{
+  "removed_env": [
+    "RUSTC_BOOTSTRAP"
+  ],
+  "...": "{...}"
+}
+"#]]
+            .is_json(),
+        )
+        .with_stderr_data(str![[r#"
+[WARNING] not passing `RUSTC_BOOTSTRAP` to cargo as it lets rustc accept unstable flags that change its output; use `--env RUSTC_BOOTSTRAP=...` to pass it anyway
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+
+    p.change_file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }\n");
+    p.cargo_("fixit --allow-no-vcs --env RUSTC_BOOTSTRAP=1")
+        .env("RUSTC_BOOTSTRAP", "1")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn cargo_build_rustflags() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            "#[cfg(foo)]\npub fn f() { let mut x = 3; let _ = x; }\n",
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs")
+        .env(
+            "CARGO_BUILD_RUSTFLAGS",
+            "--cfg foo --check-cfg cfg(foo) --cap-lints allow",
+        )
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[WARNING] ignoring `--cap-lints allow` from `CARGO_BUILD_RUSTFLAGS` as cargo-fixit needs to control lint capping
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("let x = 3;"));
+}
+
+#[cargo_test]
+fn config_rustflags() {
+    let p = project()
+        .file(
+            ".cargo/config.toml",
+            r#"
+[build]
+rustflags = ["--cfg", "foo", "--check-cfg", "cfg(foo)"]
+"#,
+        )
+        .file(
+            "src/lib.rs",
+            "#[cfg(all(foo, bar))]\npub fn f() { let mut x = 3; let _ = x; }\n",
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs")
+        .arg("--config")
+        .arg(r#"build.rustflags=["--cfg", "bar", "--check-cfg", "cfg(bar)"]"#)
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("let x = 3;"));
+}
+
+#[cargo_test]
+fn config_rustflags_literal_strings() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            "#[cfg(foo)]\npub fn f() { let mut x = 3; let _ = x; }\n",
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs")
+        .arg("--config")
+        .arg("build.rustflags = ['--cfg', 'foo', '--check-cfg', 'cfg(foo)']")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("let x = 3;"));
+}
+
+#[cargo_test]
+fn config_rustflags_file() {
+    let p = project()
+        .file(
+            "extra.toml",
+            r#"
+[build]
+rustflags = ["--cfg", "foo", "--check-cfg", "cfg(foo)"]
+"#,
+        )
+        .file(
+            "src/lib.rs",
+            "#[cfg(foo)]\npub fn f() { let mut x = 3; let _ = x; }\n",
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --config extra.toml")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("let x = 3;"));
+}
+
+#[cargo_test]
+fn build_mode() {
+    let p = project()
+        .file(
+            "build.rs",
+            r#"
+            fn main() {
+                let out_dir = std::env::var("OUT_DIR").unwrap();
+                std::fs::write(format!("{out_dir}/generated.rs"), "pub fn generated() {}").unwrap();
+            }
+            "#,
+        )
+        .file(
+            "src/lib.rs",
+            r#"
+            include!(concat!(env!("OUT_DIR"), "/generated.rs"));
+
+            pub fn a() {
+                let mut b = 10;
+                let _ = b;
+            }
+            "#,
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --mode build")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("let b = 10;"));
+}
+
+#[cargo_test]
+fn report_only_kind() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+            pub fn a() {
+                let mut b = 10;
+                let _ = b;
+            }
+            "#,
+        )
+        .file(
+            "tests/t.rs",
+            r#"
+            #[test]
+            fn t() {
+                let mut b = 10;
+                let _ = b;
+            }
+            "#,
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --all-targets --report-only-kind test")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+...
+[WARNING] variable does not need to be mutable
+ --> tests/t.rs:4:21
+...
+"#]])
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("let b = 10;"));
+    assert!(p.read_file("tests/t.rs").contains("let mut b = 10;"));
+}
+
+#[cargo_test]
+fn json_output() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+            pub fn a() {
+                let mut b = 10;
+                let _ = b;
+            }
+            "#,
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --json-output -")
+        .with_status(0)
+        .with_stdout_data(str![[r#"
+{
+  "run_id": "[..]",
+  "version": "[..]",
+  "rustc": "[..]",
+  "fixed": [
+    {
+      "file": "src/lib.rs",
+      "fixes": 1
+    }
+  ],
+  "remaining": [],
+  "skipped": [],
+  "clean": []
+}
+
+"#]])
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn message_format_json() {
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }\n")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --message-format json")
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "reason": "status",
+    "action": "checking",
+    "message": "foo v0.0.1"
+  },
+  {
+    "reason": "fixed",
+    "file": "src/lib.rs",
+    "fixes": 1,
+    "lints": [
+      "unused_mut"
+    ]
+  },
+  {
+    "reason": "summary",
+    "fixed": [
+      {
+        "file": "src/lib.rs",
+        "fixes": 1
+      }
+    ],
+    "...": "{...}"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .with_stderr_data("")
+        .run();
+}
+
+#[cargo_test]
+fn message_format_github() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            "pub fn f() { let mut x = 3; let _ = x; }\nfn bar() {}\n",
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --message-format github")
+        .with_status(0)
+        .with_stdout_data(str![[r#"
+::notice file=src/lib.rs,title=cargo-fixit::Fixed 1 suggestion (unused_mut)
+::warning file=src/lib.rs,line=2,endLine=2,col=4,endColumn=7,title=dead_code::function `bar` is never used
+
+"#]])
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+[WARNING] function `bar` is never used
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn conflicting_stdout_writers() {
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }\n")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --diff --json-output -")
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] `--json-output -` and `--diff` both write to stdout; write one of them to a file
+
+"#]])
+        .run();
+
+    p.cargo_("fixit --allow-no-vcs --message-format json --output-format sarif --output -")
+        .with_status(101)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "message": "`--message-format json` and `--output -` both write to stdout; write one of them to a file",
+    "reason": "error"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+    assert!(p.read_file("src/lib.rs").contains("let mut x = 3;"));
+}
+
+#[cargo_test]
+fn quiet_keeps_warnings() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            "pub fn f() { let mut x = 3; let _ = x; }\nfn bar() {}\n",
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --quiet")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[WARNING] function `bar` is never used
+ --> src/lib.rs:2:4
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn output_format_sarif() {
+    let p = project().file("src/lib.rs", "fn bar() {}\n").build();
+
+    p.cargo_("fixit --allow-no-vcs --output-format sarif --output -")
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+{
+  "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+  "version": "2.1.0",
+  "runs": [
+    {
+      "tool": {
+        "driver": {
+          "name": "cargo-fixit",
+          "version": "[..]",
+          "informationUri": "https://github.com/crate-ci/cargo-fixit",
+          "rules": [
+            {
+              "id": "dead_code"
+            }
+          ]
+        }
+      },
+      "automationDetails": {
+        "id": "[..]"
+      },
+      "columnKind": "unicodeCodePoints",
+      "results": [
+        {
+          "ruleId": "dead_code",
+          "level": "warning",
+          "message": {
+            "text": "function `bar` is never used"
+          },
+          "locations": [
+            {
+              "physicalLocation": {
+                "artifactLocation": {
+                  "uri": "src/lib.rs",
+                  "uriBaseId": "%SRCROOT%"
+                },
+                "region": {
+                  "startLine": 1,
+                  "startColumn": 4,
+                  "endLine": 1,
+                  "endColumn": 7
+                }
+              }
+            }
+          ]
+        }
+      ]
+    }
+  ]
+}
+"#]]
+            .is_json(),
+        )
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[WARNING] function `bar` is never used
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn stats() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            "pub fn f() { let mut x = 3; let _ = x; }\nfn bar() {}\n",
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --stats")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] src/lib.rs (1 fix)
+[WARNING] function `bar` is never used
+...
+Lint[..]Remaining
+dead_code         0          1
+unused_mut        1          0
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn stats_message_format_json() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            "pub fn f() { let mut x = 3; let _ = x; }\nfn bar() {}\n",
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --stats --message-format json")
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "reason": "status",
+    "action": "checking",
+    "message": "foo v0.0.1"
+  },
+  {
+    "reason": "fixed",
+    "file": "src/lib.rs",
+    "fixes": 1,
+    "lints": [
+      "unused_mut"
+    ]
+  },
+  {
+    "reason": "diagnostic",
+    "rendered": "{...}"
+  },
+  {
+    "reason": "stats",
+    "lints": {
+      "dead_code": {
+        "fixed": 0,
+        "skipped": 1
+      },
+      "unused_mut": {
+        "fixed": 1,
+        "skipped": 0
+      }
+    }
+  },
+  {
+    "reason": "summary",
+    "...": "{...}"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .with_stderr_data("")
+        .run();
+}
+
+#[cargo_test]
+fn print_command() {
+    let p = project()
+        .file("src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --print-command")
+        .with_stderr_data(str![[r#"
+[RUNNING] `[..]RUSTFLAGS=[..]--cap-lints=warn[..] check --message-format json`
+...
+[FIXED] src/lib.rs (1 fix)
+...
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn future_incompat_report() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            edition = "2021"
+
+            [dependencies]
+            bar = { path = "bar" }
+            "#,
+        )
+        .file("src/lib.rs", "pub fn f() { let _ = bar::h(); }")
+        .file("bar/Cargo.toml", &basic_manifest("bar", "0.1.0"))
+        .file(
+            "bar/src/lib.rs",
+            r#"
+            #![allow(dependency_on_unit_never_type_fallback)]
+            pub fn g<T: Default>() -> Result<T, ()> { Ok(T::default()) }
+            pub fn h() -> Result<(), ()> {
+                let _ = g()?;
+                Ok(())
+            }
+            "#,
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --future-incompat-report")
+        .with_stderr_data(str![[r#"
+...
+[WARNING] the following packages contain code that will be rejected by a future version of Rust
+[NOTE] in dependencies: bar v0.1.0 ([ROOT]/foo/bar)
+[NOTE] run `cargo report future-incompatibilities` for the details
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn clean_packages() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+            [workspace]
+            members = ["a", "b"]
+            "#,
+        )
+        .file("a/Cargo.toml", &basic_manifest("a", "0.1.0"))
+        .file("a/src/lib.rs", "pub fn f() { let mut x = 3; let _ = x; }")
+        .file("b/Cargo.toml", &basic_manifest("b", "0.1.0"))
+        .file("b/src/lib.rs", "pub fn f() {}")
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --json-output -")
+        .with_stdout_data(str![[r#"
+...
+  "clean": [
+    "b v0.1.0"
+  ]
+}
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn export_lint_stats() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+            pub fn a() {
+                let mut b = 10;
+                let _ = b;
+            }
+
+            fn unused() {}
+            "#,
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --export-lint-stats stats.json")
+        .with_status(0)
+        .run();
+    assert_ui().eq(
+        p.read_file("stats.json"),
+        str![[r#"
+{
+  "lints": {
+    "dead_code": {
+      "fixed": 0,
+      "skipped": 1
+    },
+    "unused_mut": {
+      "fixed": 1,
+      "skipped": 0
+    }
+  },
+  "version": "[..]"
+}
+
+"#]],
+    );
+}
+
+#[cargo_test]
+fn export_lint_stats_rechecked() {
+    // `t2` is checked after `t1` is fixed, and reports the same warning in the shared module
+    let p = project()
+        .file("src/lib.rs", "")
+        .file("tests/t1.rs", "mod common; #[test] fn t1() { common::f(); }")
+        .file("tests/t2.rs", "mod common; #[test] fn t2() { common::f(); }")
+        .file(
+            "tests/common/mod.rs",
+            "pub fn f() { let mut x = 3; let _ = x; }\nfn unused() {}\n",
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs --tests --export-lint-stats stats.json")
+        .with_stderr_data(
+            str![[r#"
+[CHECKING] foo v0.0.1
+[FIXED] tests/common/mod.rs (1 fix)
+[WARNING] function `unused` is never used
+ --> tests/common/mod.rs:2:4
+  |
+2 | fn unused() {}
+  |    ^^^^^^
+  |
+  = [NOTE] `#[warn(dead_code)]` (part of `#[warn(unused)]`) on by default
+
+
+"#]],
+        )
+        .run();
+    assert_ui().eq(
+        p.read_file("stats.json"),
+        str![[r#"
+{
+  "lints": {
+    "dead_code": {
+      "fixed": 0,
+      "skipped": 1
+    },
+    "unused_mut": {
+      "fixed": 1,
+      "skipped": 0
+    }
+  },
+  "version": "[..]"
+}
+
+"#]],
+    );
+}
+
+#[cargo_test]
+fn no_color() {
+    let p = project()
+        .file(
+            "src/lib.rs",
+            r#"
+            fn unused() {}
+            "#,
+        )
+        .build();
+
+    p.cargo_("fixit --allow-no-vcs")
+        .env("NO_COLOR", "1")
+        .env("CLICOLOR_FORCE", "1")
+        .with_status(0)
+        .with_stderr_data(str![[r#"
+[CHECKING] foo v0.0.1
+[WARNING] function `unused` is never used
+ --> src/lib.rs:2:16
+  |
+2 |             fn unused() {}
+  |                ^^^^^^
+  |
+  = [NOTE] `#[warn(dead_code)]` [..]on by default
+
+
+"#]])
+        .run();
+}